@@ -0,0 +1,206 @@
+use std::{
+    convert::TryInto,
+    io::Read,
+};
+
+/// The magic number of lossless bitmap data, with 32 bits per pixel.
+const LOSSLESS_ARGB_MAGIC: u16 = 0x0503;
+
+/// The magic number of jpeg bitmap data.
+const JPEG_MAGIC: u16 = 0xD8FF;
+
+/// The size of the header of lossless bitmap data.
+const LOSSLESS_HEADER_SIZE: usize = 26;
+
+/// An error that occured while decoding a bitmap
+#[derive(Debug, thiserror::Error)]
+pub enum BitmapError {
+    #[error("Unexpected EOF")]
+    UnexpectedEOF,
+
+    #[error("Unsupported bitmap format 0x{0:04X}")]
+    UnsupportedFormat(u16),
+
+    #[error("Invalid bitmap size")]
+    InvalidSize,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Jpeg(#[from] jpeg_decoder::Error),
+}
+
+/// A decoded bitmap.
+///
+/// Pixels are stored row by row as premultiplied ARGB, which is the format raqote uses.
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u32>,
+}
+
+impl Bitmap {
+    /// Decode bitmap data from the `bin` folder of an fla.
+    ///
+    /// Lossless data is stored as zlib-compressed ARGB, while lossy data is stored as a jpeg.
+    pub fn from_dat(data: &[u8]) -> Result<Self, BitmapError> {
+        let magic = read_u16(data, 0)?;
+        match magic {
+            LOSSLESS_ARGB_MAGIC => Self::from_lossless_dat(data),
+            JPEG_MAGIC => Self::from_jpeg(data),
+            magic => Err(BitmapError::UnsupportedFormat(magic)),
+        }
+    }
+
+    fn from_lossless_dat(data: &[u8]) -> Result<Self, BitmapError> {
+        let width = u32::from(read_u16(data, 4)?);
+        let height = u32::from(read_u16(data, 6)?);
+        let compressed = *data.get(25).ok_or(BitmapError::UnexpectedEOF)? != 0;
+
+        let mut body = data
+            .get(LOSSLESS_HEADER_SIZE..)
+            .ok_or(BitmapError::UnexpectedEOF)?;
+        let pixels = if compressed {
+            // The zlib stream is split into chunks, each prefixed with its length.
+            let mut zlib = Vec::with_capacity(body.len());
+            loop {
+                let len = usize::from(read_u16(body, 0)?);
+                if len == 0 {
+                    break;
+                }
+
+                zlib.extend_from_slice(body.get(2..2 + len).ok_or(BitmapError::UnexpectedEOF)?);
+                body = &body[2 + len..];
+            }
+
+            let mut pixels = Vec::new();
+            flate2::read::ZlibDecoder::new(zlib.as_slice()).read_to_end(&mut pixels)?;
+            pixels
+        } else {
+            body.to_vec()
+        };
+
+        let len = (width * height) as usize;
+        if pixels.len() < len * 4 {
+            return Err(BitmapError::InvalidSize);
+        }
+
+        let data = pixels
+            .chunks_exact(4)
+            .take(len)
+            .map(|argb| u32::from_be_bytes(argb.try_into().unwrap()))
+            .collect();
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    fn from_jpeg(data: &[u8]) -> Result<Self, BitmapError> {
+        let mut decoder = jpeg_decoder::Decoder::new(data);
+        let pixels = decoder.decode()?;
+        let info = decoder.info().ok_or(BitmapError::UnexpectedEOF)?;
+
+        let data: Vec<u32> = match info.pixel_format {
+            jpeg_decoder::PixelFormat::L8 => pixels
+                .iter()
+                .map(|&l| u32::from_be_bytes([0xFF, l, l, l]))
+                .collect(),
+            jpeg_decoder::PixelFormat::RGB24 => pixels
+                .chunks_exact(3)
+                .map(|rgb| u32::from_be_bytes([0xFF, rgb[0], rgb[1], rgb[2]]))
+                .collect(),
+            jpeg_decoder::PixelFormat::CMYK32 => pixels
+                .chunks_exact(4)
+                .map(|cmyk| {
+                    let k = u32::from(255 - cmyk[3]);
+                    let channel = |c: u8| ((u32::from(255 - c) * k) / 255) as u8;
+                    u32::from_be_bytes([0xFF, channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2])])
+                })
+                .collect(),
+        };
+
+        let width = u32::from(info.width);
+        let height = u32::from(info.height);
+        if data.len() != (width * height) as usize {
+            return Err(BitmapError::InvalidSize);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, BitmapError> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .ok_or(BitmapError::UnexpectedEOF)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    fn lossless_dat(width: u16, height: u16, pixels: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(pixels).unwrap();
+        let zlib = encoder.finish().unwrap();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&LOSSLESS_ARGB_MAGIC.to_le_bytes());
+        data.extend_from_slice(&(width * 4).to_le_bytes());
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        for frame in [0, u32::from(width) * 20, 0, u32::from(height) * 20].iter() {
+            data.extend_from_slice(&frame.to_le_bytes());
+        }
+        data.extend_from_slice(&[1, 1]);
+
+        // Split the stream to make sure chunks are joined
+        for chunk in zlib.chunks(5) {
+            data.extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+            data.extend_from_slice(chunk);
+        }
+        data.extend_from_slice(&[0, 0]);
+
+        data
+    }
+
+    #[test]
+    fn decode_lossless() {
+        let pixels = [
+            0xFF, 0xFF, 0x00, 0x00, // Red
+            0x80, 0x00, 0x80, 0x00, // Half transparent green
+        ];
+        let bitmap = Bitmap::from_dat(&lossless_dat(2, 1, &pixels)).unwrap();
+
+        assert_eq!(bitmap.width, 2);
+        assert_eq!(bitmap.height, 1);
+        assert_eq!(bitmap.data, [0xFF_FF_00_00, 0x80_00_80_00]);
+    }
+
+    #[test]
+    fn decode_truncated() {
+        let mut data = lossless_dat(4, 4, &[0; 4]);
+        assert!(matches!(
+            Bitmap::from_dat(&data),
+            Err(BitmapError::InvalidSize)
+        ));
+
+        data.truncate(10);
+        assert!(matches!(
+            Bitmap::from_dat(&data),
+            Err(BitmapError::UnexpectedEOF)
+        ));
+    }
+}
@@ -0,0 +1,905 @@
+use crate::{
+    player::{
+        Player,
+        Playhead,
+    },
+    sampler::{
+        check_symbol_depth,
+        SampleError,
+        SampledElement,
+        SampledElementKind,
+        TimelineSampler,
+    },
+    types::{
+        dom_symbol_instance::BlendMode,
+        dom_timeline::LayerPolicy,
+        matrix::TWIPS_PER_PIXEL,
+        scale_grid::ScaleGridMapping,
+        ColorTransform,
+        DomBitmapInstance,
+        DomBitmapItem,
+        DomShape,
+        DomSymbol,
+        DomSymbolInstance,
+        DomText,
+        DomTimeline,
+        Filter,
+        Matrix,
+        Matrix3D,
+        Perspective,
+    },
+    Fla,
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+};
+
+/// Everything a symbol or scene shows at a frame, flattened into primitives.
+///
+/// Symbol instances are resolved recursively, so the list only holds shapes, bitmaps and text,
+/// each with the transforms inherited from the instances holding it.
+/// Masks, filters and blend modes apply to several primitives at once,
+/// so primitives reference the scopes holding them instead.
+#[derive(Debug, Default)]
+pub struct DisplayList<'a> {
+    /// The primitives, in the order they should be drawn.
+    pub items: Vec<DisplayItem<'a>>,
+
+    /// The masks clipping primitives, referenced by index from `DisplayScope::Mask`.
+    pub masks: Vec<DisplayMask<'a>>,
+
+    /// The masks and groups holding primitives, referenced by index from `DisplayItem::scopes`.
+    pub scopes: Vec<DisplayScope<'a>>,
+}
+
+impl<'a> DisplayList<'a> {
+    /// Resolve the timeline of a symbol at a frame, 0-indexed.
+    ///
+    /// Nested movie clips show the frame they would show if they were placed at the same time as their keyframe.
+    pub fn from_symbol(
+        fla: &'a Fla,
+        symbol: &'a DomSymbol,
+        frame_index: usize,
+        layer_policy: LayerPolicy,
+    ) -> Result<Self, SampleError> {
+        Self::from_timeline(
+            fla,
+            &symbol.timeline.dom_timeline,
+            frame_index,
+            layer_policy,
+        )
+    }
+
+    /// Resolve a timeline, like a scene, at a frame, 0-indexed.
+    pub fn from_timeline(
+        fla: &'a Fla,
+        timeline: &'a DomTimeline,
+        frame_index: usize,
+        layer_policy: LayerPolicy,
+    ) -> Result<Self, SampleError> {
+        Self::from_playhead(fla, timeline, frame_index, None, layer_policy)
+    }
+
+    /// Resolve the current frame of a player, where every movie clip shows the frame of its own playhead.
+    ///
+    /// Layers are skipped according to the layer policy of the player.
+    pub fn from_player(fla: &'a Fla, player: &Player<'a>) -> Result<Self, SampleError> {
+        Self::from_playhead(
+            fla,
+            &player.get_symbol().timeline.dom_timeline,
+            player.get_frame_index(),
+            Some(player.get_playhead()),
+            *player.get_layer_policy(),
+        )
+    }
+
+    /// Resolve a timeline at a frame, where movie clips show the frames of their playheads if a playhead is given.
+    pub(crate) fn from_playhead(
+        fla: &'a Fla,
+        timeline: &'a DomTimeline,
+        frame_index: usize,
+        playhead: Option<&Playhead>,
+        layer_policy: LayerPolicy,
+    ) -> Result<Self, SampleError> {
+        let mut builder = DisplayListBuilder::new(fla, layer_policy);
+        let mut items = Vec::new();
+        builder.add_timeline(
+            &mut items,
+            timeline,
+            frame_index,
+            playhead,
+            &DisplayState::default(),
+        )?;
+
+        Ok(Self {
+            items,
+            masks: builder.masks,
+            scopes: builder.scopes,
+        })
+    }
+
+    /// Get the masks clipping a primitive.
+    ///
+    /// Nested masks are all applied, so the primitive is only shown where every mask is.
+    pub fn get_masks<'b>(
+        &'b self,
+        item: &'b DisplayItem<'a>,
+    ) -> impl Iterator<Item = &'b DisplayMask<'a>> {
+        item.scopes
+            .iter()
+            .filter_map(move |scope_index| match self.scopes.get(*scope_index)? {
+                DisplayScope::Mask(mask_index) => self.masks.get(*mask_index),
+                DisplayScope::Group(_) => None,
+            })
+    }
+}
+
+/// A shape, bitmap or text, placed in the symbol or scene the display list was made from.
+#[derive(Debug)]
+pub struct DisplayItem<'a> {
+    pub primitive: Primitive<'a>,
+
+    /// Maps the primitive, in pixels, to the pixels of the root symbol.
+    ///
+    /// Shapes of a symbol scaled with a 9-slice grid are first mapped by `scale_grid`.
+    pub matrix: Matrix,
+
+    /// The color transform of the primitive, combined with the color transforms of the instances holding it.
+    ///
+    /// Color transforms of instances drawn as a group are applied by the group instead.
+    pub color_transform: ColorTransform,
+
+    /// The 9-slice mapping of a shape, if the symbol holding it is scaled with a 9-slice grid.
+    pub scale_grid: Option<ScaleGridTransform>,
+
+    /// The 3D transform of the primitive, if it is inside an instance moved with the 3D tools.
+    ///
+    /// This replaces `matrix`, which only holds the 2D part of the 3D transform.
+    pub projection: Option<DisplayProjection>,
+
+    /// The masks and groups holding this primitive, from the outermost to the innermost,
+    /// as indices into `DisplayList::scopes`.
+    pub scopes: Vec<usize>,
+
+    /// The layer holding the primitive, in the timeline holding it.
+    pub layer_index: usize,
+}
+
+impl DisplayItem<'_> {
+    /// Get how much the primitive is scaled, on average, when it is mapped to the root symbol.
+    ///
+    /// This scales stroke widths, which do not follow the 9-slice grid.
+    pub fn get_scale(&self) -> f64 {
+        let matrix = match self.projection.as_ref() {
+            Some(projection) => projection.inner.then(&projection.outer),
+            None => self.matrix,
+        };
+
+        matrix.to_transform().determinant().abs().sqrt()
+    }
+
+    /// Map a point of the primitive, in twips, to the twips of the root symbol.
+    pub fn map_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = match self.scale_grid.as_ref() {
+            Some(scale_grid) => scale_grid.map_point(x, y),
+            None => (x, y),
+        };
+        if let Some(projection) = self.projection.as_ref() {
+            return projection.map_point(x, y);
+        }
+
+        let (x, y) = self
+            .matrix
+            .transform_point(x / TWIPS_PER_PIXEL, y / TWIPS_PER_PIXEL);
+
+        (x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL)
+    }
+}
+
+/// The transform of a shape inside a symbol scaled with a 9-slice grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleGridTransform {
+    /// Maps the shape to the symbol with the grid, in pixels.
+    pub matrix: Matrix,
+
+    pub mapping: ScaleGridMapping,
+}
+
+impl ScaleGridTransform {
+    /// Map a point of the shape, in twips, to the twips of the symbol, before it is scaled by the instance matrix.
+    pub fn map_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = self
+            .matrix
+            .transform_point(x / TWIPS_PER_PIXEL, y / TWIPS_PER_PIXEL);
+        self.mapping
+            .map_point_unscaled(x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL)
+    }
+}
+
+/// The transforms of a primitive inside an instance moved with the 3D tools.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayProjection {
+    /// Maps the primitive, in pixels, to the pixels of the 3D instance's symbol.
+    pub inner: Matrix,
+
+    pub matrix_3d: Matrix3D,
+
+    /// Maps the pixels of the 3D instance's parent to the pixels of the root symbol.
+    pub outer: Matrix,
+
+    pub perspective: Perspective,
+}
+
+impl DisplayProjection {
+    /// Map a point of the primitive, in twips, to the twips of the root symbol.
+    pub fn map_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = self
+            .inner
+            .transform_point(x / TWIPS_PER_PIXEL, y / TWIPS_PER_PIXEL);
+        let (x, y, z) = self.matrix_3d.transform_point(x, y, 0.0);
+        let (x, y) = self.outer.transform_point(x, y);
+
+        // Depth is scaled along with the parent of the 3D instance.
+        // Points behind the viewer are moved just in front of it, so paths stay closed.
+        let scale = self.outer.to_transform().determinant().abs().sqrt();
+        let z = (z * scale).max(-0.99 * self.perspective.focal_length);
+        let (x, y) = self.perspective.project(x, y, z).unwrap_or((x, y));
+
+        (x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL)
+    }
+}
+
+/// A primitive of a display list.
+#[derive(Debug)]
+pub enum Primitive<'a> {
+    /// A shape, which is owned if it is morphed by a shape tween.
+    Shape(Cow<'a, DomShape>),
+    Bitmap {
+        bitmap_instance: &'a DomBitmapInstance,
+        bitmap_item: &'a DomBitmapItem,
+    },
+    Text(&'a DomText),
+}
+
+/// Primitives that are drawn together, before something is applied to all of them.
+#[derive(Debug)]
+pub enum DisplayScope<'a> {
+    /// The primitives of the layers masked by a mask layer, clipped by the mask at an index of `DisplayList::masks`.
+    Mask(usize),
+
+    /// The primitives of an instance with filters or a blend mode.
+    Group(DisplayGroup<'a>),
+}
+
+/// An instance with filters or a blend mode, which apply to the instance as a whole.
+#[derive(Debug)]
+pub struct DisplayGroup<'a> {
+    /// The filters of the instance, in the order they are applied.
+    pub filters: Cow<'a, [Filter]>,
+
+    /// The color transform of the instance, combined with the color transforms of the instances holding it.
+    ///
+    /// This applies to the filtered group, so it fades shadows and glows too.
+    pub color_transform: ColorTransform,
+
+    pub blend_mode: BlendMode,
+}
+
+/// The content of a mask layer, clipping the layers it masks.
+///
+/// Masked primitives are shown where the mask is drawn, scaled by its alpha.
+/// The color transforms of the mask's primitives make them opaque, so only antialiasing and filters fade the mask.
+/// The scopes of the mask's primitives do not include the scopes of the masked primitives.
+#[derive(Debug)]
+pub struct DisplayMask<'a> {
+    pub items: Vec<DisplayItem<'a>>,
+}
+
+/// Makes colors opaque, keeping their red, green and blue.
+const OPAQUE: ColorTransform = ColorTransform {
+    multipliers: [1.0; 4],
+    offsets: [0.0, 0.0, 0.0, 255.0],
+};
+
+/// The state inherited by the elements of a timeline.
+#[derive(Debug, Clone, Default)]
+struct DisplayState {
+    /// Maps the timeline's pixels to the pixels of the root symbol.
+    matrix: Matrix,
+
+    color_transform: ColorTransform,
+
+    /// The 9-slice mapping of the timeline, if it is scaled with a 9-slice grid.
+    scale_grid: Option<ScaleGridMapping>,
+
+    /// The 3D transform of the timeline, if it is inside a 3D instance.
+    projection: Option<DisplayProjection>,
+
+    scopes: Vec<usize>,
+
+    /// Whether the timeline is inside an instance with the `Layer` blend mode.
+    in_layer_group: bool,
+
+    /// Whether the timeline is part of a mask.
+    in_mask: bool,
+
+    /// The number of symbols the timeline is nested in.
+    depth: usize,
+}
+
+struct DisplayListBuilder<'a> {
+    fla: &'a Fla,
+    sampler: TimelineSampler<'a>,
+    masks: Vec<DisplayMask<'a>>,
+    scopes: Vec<DisplayScope<'a>>,
+}
+
+impl<'a> DisplayListBuilder<'a> {
+    fn new(fla: &'a Fla, layer_policy: LayerPolicy) -> Self {
+        Self {
+            fla,
+            sampler: TimelineSampler::new(fla, layer_policy),
+            masks: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+
+    fn add_timeline(
+        &mut self,
+        items: &mut Vec<DisplayItem<'a>>,
+        timeline: &'a DomTimeline,
+        frame_index: usize,
+        playhead: Option<&Playhead>,
+        state: &DisplayState,
+    ) -> Result<(), SampleError> {
+        let elements = self.sampler.sample_timeline(timeline, frame_index)?;
+
+        // Each mask layer is resolved once, the first time a layer it masks is drawn.
+        let mut mask_scopes = HashMap::new();
+        for sampled in elements.iter() {
+            let mask_index = timeline
+                .get_mask_index(sampled.layer_index)
+                .filter(|mask_index| {
+                    timeline.should_render_layer(*mask_index, self.sampler.get_layer_policy())
+                });
+            let mask_index = match mask_index {
+                Some(mask_index) => mask_index,
+                None => {
+                    // Mask layers are only drawn as the clip of their masked layers.
+                    if !timeline
+                        .get_layer(sampled.layer_index)
+                        .is_some_and(|layer| layer.is_mask())
+                    {
+                        self.add_element(items, sampled, playhead, state)?;
+                    }
+                    continue;
+                }
+            };
+
+            let scope = match mask_scopes.get(&mask_index) {
+                Some(scope) => *scope,
+                None => {
+                    // The mask is drawn on its own, outside of the scopes of the masked primitives.
+                    // Only its shape clips, so its content is drawn opaque, whatever its alpha.
+                    let mask_state = DisplayState {
+                        color_transform: OPAQUE,
+                        scopes: Vec::new(),
+                        in_mask: true,
+                        ..state.clone()
+                    };
+                    let mut mask_items = Vec::new();
+                    for mask_sampled in elements
+                        .iter()
+                        .filter(|mask_sampled| mask_sampled.layer_index == mask_index)
+                    {
+                        self.add_element(&mut mask_items, mask_sampled, playhead, &mask_state)?;
+                    }
+                    self.masks.push(DisplayMask { items: mask_items });
+                    self.scopes.push(DisplayScope::Mask(self.masks.len() - 1));
+                    mask_scopes.insert(mask_index, self.scopes.len() - 1);
+
+                    self.scopes.len() - 1
+                }
+            };
+
+            let mut masked_state = state.clone();
+            masked_state.scopes.push(scope);
+            self.add_element(items, sampled, playhead, &masked_state)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_element(
+        &mut self,
+        items: &mut Vec<DisplayItem<'a>>,
+        sampled: &SampledElement<'a>,
+        playhead: Option<&Playhead>,
+        state: &DisplayState,
+    ) -> Result<(), SampleError> {
+        let (primitive, matrix, scale_grid) = match &sampled.kind {
+            // The grid maps shapes to the symbol, which is then transformed by the instance.
+            SampledElementKind::Shape(shape) => match state.scale_grid {
+                Some(mapping) => (
+                    Primitive::Shape(shape.clone()),
+                    Matrix::IDENTITY,
+                    Some(ScaleGridTransform {
+                        matrix: sampled.matrix,
+                        mapping,
+                    }),
+                ),
+                None => (Primitive::Shape(shape.clone()), sampled.matrix, None),
+            },
+            SampledElementKind::Symbol {
+                symbol_instance,
+                symbol,
+                frame_index,
+            } => {
+                return self.add_symbol(
+                    items,
+                    sampled,
+                    symbol_instance,
+                    symbol,
+                    *frame_index,
+                    playhead,
+                    state,
+                );
+            }
+            SampledElementKind::Bitmap {
+                bitmap_instance,
+                bitmap_item,
+            } => (
+                Primitive::Bitmap {
+                    bitmap_instance,
+                    bitmap_item,
+                },
+                sampled.matrix,
+                None,
+            ),
+            SampledElementKind::Text(text) => (Primitive::Text(text), sampled.matrix, None),
+        };
+
+        items.push(DisplayItem {
+            primitive,
+            matrix: matrix.then(&state.matrix),
+            color_transform: sampled.color_transform.then(&state.color_transform),
+            scale_grid,
+            projection: state.projection.map(|projection| DisplayProjection {
+                inner: matrix.then(&projection.inner),
+                ..projection
+            }),
+            scopes: state.scopes.clone(),
+            layer_index: sampled.layer_index,
+        });
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_symbol(
+        &mut self,
+        items: &mut Vec<DisplayItem<'a>>,
+        sampled: &SampledElement<'a>,
+        symbol_instance: &DomSymbolInstance,
+        symbol: &'a DomSymbol,
+        frame_index: usize,
+        playhead: Option<&Playhead>,
+        state: &DisplayState,
+    ) -> Result<(), SampleError> {
+        check_symbol_depth(symbol, state.depth + 1)?;
+
+        let child = playhead.and_then(|playhead| playhead.get_child(sampled));
+        let frame_index = child
+            .map(|child| child.get_frame_index())
+            .unwrap_or(frame_index);
+
+        // Instances nested in a 3D instance only use the 2D part of their 3D transform.
+        let matrix_3d = symbol_instance
+            .get_matrix_3d()
+            .filter(|_| state.projection.is_none());
+
+        // A 9-slice grid replaces the scale of the instance for the shapes of its symbol.
+        let scale_grid = match matrix_3d {
+            Some(_) => None,
+            None => symbol.get_scale_grid_mapping(
+                self.fla,
+                self.sampler.get_layer_policy(),
+                &sampled.matrix,
+            ),
+        };
+
+        let projection = match matrix_3d {
+            Some(matrix_3d) => Some(DisplayProjection {
+                inner: Matrix::IDENTITY,
+                matrix_3d,
+                outer: state.matrix,
+                perspective: self.fla.dom_document.get_perspective(),
+            }),
+            None => state.projection.map(|projection| DisplayProjection {
+                inner: sampled.matrix.then(&projection.inner),
+                ..projection
+            }),
+        };
+
+        // `Alpha` and `Erase` only affect the layer of an ancestor with the `Layer` blend mode.
+        let blend_mode = match symbol_instance.get_blend_mode() {
+            BlendMode::Alpha | BlendMode::Erase if !state.in_layer_group => BlendMode::Normal,
+            blend_mode => blend_mode,
+        };
+
+        let mut child_state = DisplayState {
+            matrix: sampled.matrix.then(&state.matrix),
+            color_transform: sampled.color_transform.then(&state.color_transform),
+            scale_grid,
+            projection,
+            scopes: state.scopes.clone(),
+            in_layer_group: state.in_layer_group || blend_mode == BlendMode::Layer,
+            in_mask: state.in_mask,
+            depth: state.depth + 1,
+        };
+
+        // Filters and blend modes apply to the instance as a whole, so its primitives are drawn as a group.
+        let has_filters = sampled
+            .filters
+            .iter()
+            .any(|filter| !matches!(filter, Filter::Unknown));
+        // Masks keep the color transform on their primitives, as it makes them opaque.
+        if blend_mode != BlendMode::Normal || has_filters {
+            let color_transform = if state.in_mask {
+                ColorTransform::IDENTITY
+            } else {
+                std::mem::take(&mut child_state.color_transform)
+            };
+            self.scopes.push(DisplayScope::Group(DisplayGroup {
+                filters: sampled.filters.clone(),
+                color_transform,
+                blend_mode,
+            }));
+            child_state.scopes.push(self.scopes.len() - 1);
+        }
+
+        self.add_timeline(
+            items,
+            &symbol.timeline.dom_timeline,
+            frame_index,
+            child,
+            &child_state,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        dot,
+        frame,
+        instance,
+        layer,
+        make_fla,
+        square,
+        symbol,
+    };
+
+    /// Resolve a frame of a symbol, 0-indexed.
+    fn resolve<'a>(fla: &'a Fla, name: &str, frame_index: usize) -> DisplayList<'a> {
+        DisplayList::from_symbol(
+            fla,
+            fla.get_symbol(name).unwrap(),
+            frame_index,
+            LayerPolicy::default(),
+        )
+        .unwrap()
+    }
+
+    /// Get the blend modes of the groups holding an item, from the outermost to the innermost.
+    fn group_blend_modes(display_list: &DisplayList, item: &DisplayItem) -> Vec<BlendMode> {
+        item.scopes
+            .iter()
+            .filter_map(|scope| match &display_list.scopes[*scope] {
+                DisplayScope::Group(group) => Some(group.blend_mode),
+                DisplayScope::Mask(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_transforms() {
+        let fla = make_fla(
+            &[
+                dot(),
+                (
+                    "Mid",
+                    symbol(
+                        "Mid",
+                        "",
+                        &layer(
+                            "",
+                            &frame(
+                                0,
+                                "",
+                                &instance(
+                                    "Dot",
+                                    "",
+                                    r#"<matrix><Matrix a="2" d="2" ty="5"/></matrix><color><Color redOffset="10"/></color>"#,
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &frame(
+                                0,
+                                "",
+                                &instance(
+                                    "Mid",
+                                    "",
+                                    r#"<matrix><Matrix tx="10"/></matrix><color><Color alphaMultiplier="0.5"/></color>"#,
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The inner instance is applied first, then the outer one.
+        let display_list = resolve(&fla, "Main", 0);
+        let item = &display_list.items[0];
+        assert_eq!(item.map_point(0.0, 0.0), (200.0, 100.0));
+        assert_eq!(item.map_point(200.0, 200.0), (600.0, 500.0));
+        assert_eq!(
+            item.color_transform.apply([100, 0, 0, 255]),
+            [110, 0, 0, 128]
+        );
+    }
+
+    #[test]
+    fn masked_layers() {
+        let layers = layer(r#"layerType="mask""#, &frame(0, "", &square("#0000FF", 10)))
+            + &layer(
+                r#"parentLayerIndex="0""#,
+                &frame(0, "", &square("#FF0000", 20)),
+            )
+            + &layer(
+                r#"parentLayerIndex="0""#,
+                &frame(0, "", &square("#00FF00", 30)),
+            )
+            + &layer("", &frame(0, "", &square("#FFFFFF", 40)));
+        let fla = make_fla(&[("Main", symbol("Main", "", &layers))], "", &[]);
+
+        // The mask layer is only drawn as the mask of the layers it masks, which share it.
+        let display_list = resolve(&fla, "Main", 0);
+        let layer_indices: Vec<_> = display_list
+            .items
+            .iter()
+            .map(|item| item.layer_index)
+            .collect();
+        assert_eq!(layer_indices, [3, 2, 1]);
+        assert_eq!(display_list.masks.len(), 1);
+        assert_eq!(display_list.masks[0].items.len(), 1);
+        assert_eq!(display_list.masks[0].items[0].layer_index, 0);
+
+        let items = &display_list.items;
+        assert!(items[0].scopes.is_empty());
+        assert_eq!(items[1].scopes, items[2].scopes);
+        assert!(matches!(
+            display_list.scopes[items[1].scopes[0]],
+            DisplayScope::Mask(0)
+        ));
+        assert_eq!(display_list.get_masks(&items[0]).count(), 0);
+        assert_eq!(display_list.get_masks(&items[1]).count(), 1);
+    }
+
+    #[test]
+    fn player_matches_symbol() {
+        let clip_frames: String = ["#FF0000", "#00FF00", "#0000FF", "#FFFFFF"]
+            .iter()
+            .enumerate()
+            .map(|(i, color)| frame(i, "", &square(color, 10)))
+            .collect();
+        let fla = make_fla(
+            &[
+                ("Clip", symbol("Clip", "", &layer("", &clip_frames))),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &frame(
+                                0,
+                                r#"duration="3""#,
+                                r#"<DOMSymbolInstance libraryItemName="Clip" symbolType="movie clip"/>"#,
+                            ),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // Without keyframes restarting the clip, its playhead matches the frame of the timeline holding it.
+        let mut player = Player::new(
+            &fla,
+            fla.get_symbol("Main").unwrap(),
+            LayerPolicy::default(),
+        )
+        .unwrap();
+        for frame_index in 0..3 {
+            assert_eq!(player.get_frame_index(), frame_index);
+            let from_player = DisplayList::from_player(&fla, &player).unwrap();
+            let from_symbol = resolve(&fla, "Main", frame_index);
+            assert_eq!(format!("{:?}", from_player), format!("{:?}", from_symbol));
+            player.tick().unwrap();
+        }
+    }
+
+    #[test]
+    fn alpha_and_erase_need_a_layer_group() {
+        let erased = instance("Dot", r#"blendMode="erase""#, "")
+            + &instance("Dot", r#"blendMode="alpha""#, "");
+        let fla = make_fla(
+            &[
+                dot(),
+                (
+                    "Erased",
+                    symbol("Erased", "", &layer("", &frame(0, "", &erased))),
+                ),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &(frame(0, "", &instance("Erased", "", ""))
+                                + &frame(1, "", &instance("Erased", r#"blendMode="layer""#, ""))),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // Without a layer group, there is nothing for them to erase but what is below the symbol.
+        let display_list = resolve(&fla, "Main", 0);
+        assert_eq!(display_list.items.len(), 2);
+        for item in display_list.items.iter() {
+            assert!(group_blend_modes(&display_list, item).is_empty());
+        }
+
+        let display_list = resolve(&fla, "Main", 1);
+        assert_eq!(
+            group_blend_modes(&display_list, &display_list.items[0]),
+            [BlendMode::Layer, BlendMode::Erase]
+        );
+        assert_eq!(
+            group_blend_modes(&display_list, &display_list.items[1]),
+            [BlendMode::Layer, BlendMode::Alpha]
+        );
+    }
+
+    #[test]
+    fn masks_are_opaque() {
+        let layers = layer(
+            r#"layerType="mask""#,
+            &frame(
+                0,
+                "",
+                &instance(
+                    "Dot",
+                    r#"blendMode="layer""#,
+                    r#"<color><Color alphaMultiplier="0.25"/></color>"#,
+                ),
+            ),
+        ) + &layer(
+            r#"parentLayerIndex="0""#,
+            &frame(0, "", &square("#FF0000", 20)),
+        );
+        let fla = make_fla(&[dot(), ("Main", symbol("Main", "", &layers))], "", &[]);
+
+        // The color transform of the instance stays on its shapes, and does not fade the mask.
+        let display_list = resolve(&fla, "Main", 0);
+        let mask_item = &display_list.masks[0].items[0];
+        assert_eq!(
+            mask_item.color_transform.apply([0, 0, 255, 255]),
+            [0, 0, 255, 255]
+        );
+        match &display_list.scopes[mask_item.scopes[0]] {
+            DisplayScope::Group(group) => assert!(group.color_transform.is_identity()),
+            DisplayScope::Mask(_) => panic!("expected a group"),
+        }
+    }
+
+    #[test]
+    fn scale_grid_only_maps_shapes() {
+        let panel = square("#FF0000", 100)
+            + &square("#00FF00", 10)
+            + &instance("Dot", "", r#"<matrix><Matrix tx="45" ty="45"/></matrix>"#);
+        let fla = make_fla(
+            &[
+                dot(),
+                (
+                    "Panel",
+                    symbol(
+                        "Panel",
+                        r#"scaleGridLeft="10" scaleGridRight="90" scaleGridTop="10" scaleGridBottom="90""#,
+                        &layer("", &frame(0, "", &panel)),
+                    ),
+                ),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &frame(
+                                0,
+                                "",
+                                &instance(
+                                    "Panel",
+                                    "",
+                                    r#"<matrix><Matrix a="3" d="3" tx="10"/></matrix>"#,
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        let display_list = resolve(&fla, "Main", 0);
+        let items = &display_list.items;
+        assert_eq!(items.len(), 3);
+
+        // The shapes of the panel are mapped by the grid, so its corner keeps its size.
+        assert!(items[0].scale_grid.is_some());
+        assert_eq!(items[0].map_point(2000.0, 2000.0), (6200.0, 6000.0));
+        assert_eq!(items[1].map_point(200.0, 200.0), (400.0, 200.0));
+
+        // The nested symbol is scaled with the instance, without the grid of the panel.
+        assert!(items[2].scale_grid.is_none());
+        assert_eq!(items[2].map_point(0.0, 0.0), (2900.0, 2700.0));
+        assert_eq!(items[2].map_point(200.0, 200.0), (3500.0, 3300.0));
+    }
+
+    #[test]
+    fn recursion_limit() {
+        let elements = format!(
+            r#"{}<DOMSymbolInstance libraryItemName="Loop" symbolType="graphic"/>"#,
+            square("#FF0000", 10)
+        );
+        let fla = make_fla(
+            &[(
+                "Loop",
+                symbol("Loop", "", &layer("", &frame(0, "", &elements))),
+            )],
+            "",
+            &[],
+        );
+
+        let result = DisplayList::from_symbol(
+            &fla,
+            fla.get_symbol("Loop").unwrap(),
+            0,
+            LayerPolicy::default(),
+        );
+        assert!(matches!(result, Err(SampleError::RecursionLimit(name)) if name == "Loop"));
+    }
+}
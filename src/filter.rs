@@ -0,0 +1,657 @@
+use crate::{
+    bitmap::Bitmap,
+    types::filter::{
+        AdjustColorFilter,
+        BevelFilter,
+        BlurFilter,
+        DropShadowFilter,
+        Filter,
+        FilterType,
+        GlowFilter,
+        GradientGlowFilter,
+    },
+};
+
+/// The most passes a filter can blur with.
+const MAX_QUALITY: u64 = 15;
+
+impl Filter {
+    /// Apply this filter to a bitmap.
+    ///
+    /// `scale` is the number of bitmap pixels in a pixel of the stage.
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        match self {
+            Self::Blur(filter) => filter.apply(bitmap, scale),
+            Self::DropShadow(filter) => filter.apply(bitmap, scale),
+            Self::Glow(filter) => filter.apply(bitmap, scale),
+            Self::Bevel(filter) => filter.apply(bitmap, scale),
+            Self::GradientGlow(filter) => filter.apply(bitmap, scale),
+            Self::AdjustColor(filter) => filter.apply(bitmap),
+            Self::Unknown => {}
+        }
+    }
+}
+
+impl BlurFilter {
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        let blur = Blur::new(self.blur_x, self.blur_y, self.quality, scale);
+        let width = bitmap.width as usize;
+        let height = bitmap.height as usize;
+
+        let mut planes = split_planes(bitmap);
+        for plane in planes.iter_mut() {
+            blur.apply(plane, width, height);
+        }
+        join_planes(bitmap, &planes);
+    }
+}
+
+impl DropShadowFilter {
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        let shadow = Shadow {
+            blur: Blur::new(self.blur_x, self.blur_y, self.quality, scale),
+            strength: self.strength,
+            offset: offset(self.angle, self.distance, scale),
+            inner: self.inner,
+            knockout: self.knockout,
+            hide_object: self.hide_object,
+        };
+        shadow.apply(bitmap, |value| solid(self.color, self.alpha * value));
+    }
+}
+
+impl GlowFilter {
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        let shadow = Shadow {
+            blur: Blur::new(self.blur_x, self.blur_y, self.quality, scale),
+            strength: self.strength,
+            offset: (0, 0),
+            inner: self.inner,
+            knockout: self.knockout,
+            hide_object: false,
+        };
+        shadow.apply(bitmap, |value| solid(self.color, self.alpha * value));
+    }
+}
+
+impl BevelFilter {
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        let bevel = Bevel {
+            blur: Blur::new(self.blur_x, self.blur_y, self.quality, scale),
+            strength: self.strength,
+            offset: offset(self.angle, self.distance, scale),
+            filter_type: self.filter_type.unwrap_or(FilterType::Inner),
+            knockout: self.knockout,
+        };
+        bevel.apply(bitmap, |value| {
+            if value >= 0.0 {
+                solid(self.highlight_color, self.highlight_alpha * value)
+            } else {
+                solid(self.shadow_color, self.shadow_alpha * -value)
+            }
+        });
+    }
+}
+
+impl GradientGlowFilter {
+    pub fn apply(&self, bitmap: &mut Bitmap, scale: f64) {
+        let shadow = Shadow {
+            blur: Blur::new(self.blur_x, self.blur_y, self.quality, scale),
+            strength: self.strength,
+            offset: offset(self.angle, self.distance, scale),
+            inner: self.filter_type == Some(FilterType::Inner),
+            knockout: self.knockout,
+            hide_object: false,
+        };
+        shadow.apply(bitmap, |value| self.sample_gradient(value));
+    }
+
+    /// Get the premultiplied color of the gradient at a ratio from 0 to 1.
+    fn sample_gradient(&self, ratio: f64) -> [f64; 4] {
+        let entries = &self.gradient_entries;
+        let position = entries.iter().position(|entry| entry.ratio >= ratio);
+        let (start, end) = match position {
+            Some(0) => (&entries[0], &entries[0]),
+            Some(position) => (&entries[position - 1], &entries[position]),
+            None => match entries.last() {
+                Some(entry) => (entry, entry),
+                None => return [0.0; 4],
+            },
+        };
+
+        let t = if end.ratio > start.ratio {
+            (ratio - start.ratio) / (end.ratio - start.ratio)
+        } else {
+            0.0
+        };
+        let start_color = solid(start.color, start.alpha);
+        let end_color = solid(end.color, end.alpha);
+
+        let mut ret = [0.0; 4];
+        for (channel, (start, end)) in ret.iter_mut().zip(start_color.iter().zip(end_color)) {
+            *channel = start + (end - start) * t;
+        }
+
+        ret
+    }
+}
+
+impl AdjustColorFilter {
+    /// Get the color matrix of this filter, like the `fl.motion.AdjustColor` class.
+    ///
+    /// Each row maps a channel, in the order red, green, blue, alpha, to `dot(row[..4], rgba) + row[4]`.
+    pub fn get_color_matrix(&self) -> [[f64; 5]; 4] {
+        let mut ret = IDENTITY_COLOR_MATRIX;
+
+        let brightness = self.brightness.clamp(-100.0, 100.0);
+        for row in ret.iter_mut().take(3) {
+            row[4] = brightness;
+        }
+
+        let contrast = contrast_scale(self.contrast.clamp(-100.0, 100.0));
+        let offset = 0.5 * (127.0 - contrast * 127.0);
+        ret = concat_color_matrix(
+            &ret,
+            &[
+                [contrast, 0.0, 0.0, 0.0, offset],
+                [0.0, contrast, 0.0, 0.0, offset],
+                [0.0, 0.0, contrast, 0.0, offset],
+                [0.0, 0.0, 0.0, 1.0, 0.0],
+            ],
+        );
+
+        let saturation = self.saturation.clamp(-100.0, 100.0) / 100.0;
+        let saturation = 1.0
+            + if saturation > 0.0 {
+                saturation * 3.0
+            } else {
+                saturation
+            };
+        let (lum_r, lum_g, lum_b) = (0.3086, 0.6094, 0.0820);
+        let (r, g, b) = (
+            lum_r * (1.0 - saturation),
+            lum_g * (1.0 - saturation),
+            lum_b * (1.0 - saturation),
+        );
+        ret = concat_color_matrix(
+            &ret,
+            &[
+                [r + saturation, g, b, 0.0, 0.0],
+                [r, g + saturation, b, 0.0, 0.0],
+                [r, g, b + saturation, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0, 0.0],
+            ],
+        );
+
+        let hue = self.hue.clamp(-180.0, 180.0).to_radians();
+        let (sin, cos) = hue.sin_cos();
+        let (lum_r, lum_g, lum_b) = (0.213, 0.715, 0.072);
+        ret = concat_color_matrix(
+            &ret,
+            &[
+                [
+                    lum_r + cos * (1.0 - lum_r) + sin * -lum_r,
+                    lum_g + cos * -lum_g + sin * -lum_g,
+                    lum_b + cos * -lum_b + sin * (1.0 - lum_b),
+                    0.0,
+                    0.0,
+                ],
+                [
+                    lum_r + cos * -lum_r + sin * 0.143,
+                    lum_g + cos * (1.0 - lum_g) + sin * 0.140,
+                    lum_b + cos * -lum_b + sin * -0.283,
+                    0.0,
+                    0.0,
+                ],
+                [
+                    lum_r + cos * -lum_r + sin * -(1.0 - lum_r),
+                    lum_g + cos * -lum_g + sin * lum_g,
+                    lum_b + cos * (1.0 - lum_b) + sin * lum_b,
+                    0.0,
+                    0.0,
+                ],
+                [0.0, 0.0, 0.0, 1.0, 0.0],
+            ],
+        );
+
+        ret
+    }
+
+    pub fn apply(&self, bitmap: &mut Bitmap) {
+        let matrix = self.get_color_matrix();
+        for pixel in bitmap.data.iter_mut() {
+            let [a, r, g, b] = unpremultiply(*pixel);
+            let rgba = [r, g, b, a];
+
+            let mut out = [0.0; 4];
+            for (channel, row) in out.iter_mut().zip(matrix.iter()) {
+                let value = row[..4]
+                    .iter()
+                    .zip(rgba.iter())
+                    .map(|(weight, channel)| weight * channel)
+                    .sum::<f64>()
+                    + row[4];
+                *channel = value.clamp(0.0, 255.0);
+            }
+
+            let [r, g, b, a] = out;
+            *pixel = premultiply([a, r, g, b]);
+        }
+    }
+}
+
+const IDENTITY_COLOR_MATRIX: [[f64; 5]; 4] = [
+    [1.0, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0, 0.0],
+];
+
+/// Get a color matrix that applies `first`, then `second`.
+fn concat_color_matrix(first: &[[f64; 5]; 4], second: &[[f64; 5]; 4]) -> [[f64; 5]; 4] {
+    let mut ret = [[0.0; 5]; 4];
+    for (i, row) in ret.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|k| second[i][k] * first[k][j]).sum::<f64>();
+        }
+        row[4] += second[i][4];
+    }
+
+    ret
+}
+
+/// Get the scale of a contrast adjustment, using the same curve as Flash.
+fn contrast_scale(contrast: f64) -> f64 {
+    const DELTA_INDEX: [f64; 101] = [
+        0.0, 0.01, 0.02, 0.04, 0.05, 0.06, 0.07, 0.08, 0.1, 0.11, 0.12, 0.14, 0.15, 0.16, 0.17,
+        0.18, 0.20, 0.21, 0.22, 0.24, 0.25, 0.27, 0.28, 0.30, 0.32, 0.34, 0.36, 0.38, 0.40, 0.42,
+        0.44, 0.46, 0.48, 0.5, 0.53, 0.56, 0.59, 0.62, 0.65, 0.68, 0.71, 0.74, 0.77, 0.80, 0.83,
+        0.86, 0.89, 0.92, 0.95, 0.98, 1.0, 1.06, 1.12, 1.18, 1.24, 1.30, 1.36, 1.42, 1.48, 1.54,
+        1.60, 1.66, 1.72, 1.78, 1.84, 1.90, 1.96, 2.0, 2.12, 2.25, 2.37, 2.50, 2.62, 2.75, 2.87,
+        3.0, 3.2, 3.4, 3.6, 3.8, 4.0, 4.3, 4.7, 4.9, 5.0, 5.5, 6.0, 6.5, 6.8, 7.0, 7.3, 7.5, 7.8,
+        8.0, 8.4, 8.7, 9.0, 9.4, 9.6, 9.8, 10.0,
+    ];
+
+    let x = if contrast < 0.0 {
+        127.0 + contrast / 100.0 * 127.0
+    } else {
+        let index = contrast.floor() as usize;
+        let fraction = contrast - contrast.floor();
+        let delta = match DELTA_INDEX.get(index + 1) {
+            Some(next) => DELTA_INDEX[index] * (1.0 - fraction) + next * fraction,
+            None => DELTA_INDEX[index],
+        };
+        delta * 127.0 + 127.0
+    };
+
+    x / 127.0
+}
+
+/// A box blur, repeated once per pass. The size of the box is the blur size, like the Flash Player.
+struct Blur {
+    radius_x: usize,
+    radius_y: usize,
+    passes: u64,
+}
+
+impl Blur {
+    fn new(blur_x: f64, blur_y: f64, quality: u64, scale: f64) -> Self {
+        Self {
+            radius_x: ((blur_x * scale - 1.0) / 2.0).round().max(0.0) as usize,
+            radius_y: ((blur_y * scale - 1.0) / 2.0).round().max(0.0) as usize,
+            passes: quality.clamp(1, MAX_QUALITY),
+        }
+    }
+
+    fn apply(&self, plane: &mut [f64], width: usize, height: usize) {
+        for _ in 0..self.passes {
+            if self.radius_x > 0 {
+                for y in 0..height {
+                    box_blur(plane, y * width, 1, width, self.radius_x);
+                }
+            }
+
+            if self.radius_y > 0 {
+                for x in 0..width {
+                    box_blur(plane, x, width, height, self.radius_y);
+                }
+            }
+        }
+    }
+}
+
+/// Blur `len` values of a plane, starting at `start` and `stride` apart.
+///
+/// Values outside the plane are transparent.
+fn box_blur(plane: &mut [f64], start: usize, stride: usize, len: usize, radius: usize) {
+    let line: Vec<f64> = (0..len).map(|i| plane[start + i * stride]).collect();
+    let size = (radius * 2 + 1) as f64;
+
+    let mut sum: f64 = line.iter().take(radius).sum();
+    for i in 0..len {
+        if let Some(value) = line.get(i + radius) {
+            sum += value;
+        }
+        if i > radius {
+            sum -= line[i - radius - 1];
+        }
+
+        plane[start + i * stride] = sum / size;
+    }
+}
+
+/// Draws a blurred, colored copy of the alpha of an object, inside or outside of it.
+struct Shadow {
+    blur: Blur,
+    strength: f64,
+    offset: (isize, isize),
+    inner: bool,
+    knockout: bool,
+    hide_object: bool,
+}
+
+impl Shadow {
+    /// `color` maps the strength of the shadow at a pixel, from 0 to 1, to a premultiplied color.
+    fn apply(&self, bitmap: &mut Bitmap, color: impl Fn(f64) -> [f64; 4]) {
+        let width = bitmap.width as usize;
+        let height = bitmap.height as usize;
+        let alpha = alpha_plane(bitmap);
+
+        // Inner shadows are cast by the area outside of the object.
+        let (source, fill) = if self.inner {
+            (alpha.iter().map(|alpha| 1.0 - alpha).collect(), 1.0)
+        } else {
+            (alpha.clone(), 0.0)
+        };
+        let mut shadow = shift_plane(&source, width, height, self.offset, fill);
+        self.blur.apply(&mut shadow, width, height);
+
+        for (i, pixel) in bitmap.data.iter_mut().enumerate() {
+            let value = (shadow[i] * self.strength).clamp(0.0, 1.0);
+            let object = argb_to_floats(*pixel);
+
+            let out = if self.inner {
+                let shadow = scale_color(color(value), alpha[i]);
+                if self.knockout {
+                    shadow
+                } else {
+                    over(shadow, object)
+                }
+            } else if self.knockout {
+                scale_color(color(value), 1.0 - alpha[i])
+            } else if self.hide_object {
+                color(value)
+            } else {
+                over(object, color(value))
+            };
+            *pixel = floats_to_argb(out);
+        }
+    }
+}
+
+/// Draws highlights and shadows along the edges of an object.
+struct Bevel {
+    blur: Blur,
+    strength: f64,
+    offset: (isize, isize),
+    filter_type: FilterType,
+    knockout: bool,
+}
+
+impl Bevel {
+    /// `color` maps the strength of the bevel at a pixel, from -1 (shadow) to 1 (highlight), to a premultiplied color.
+    fn apply(&self, bitmap: &mut Bitmap, color: impl Fn(f64) -> [f64; 4]) {
+        let width = bitmap.width as usize;
+        let height = bitmap.height as usize;
+        let alpha = alpha_plane(bitmap);
+
+        let mut blurred = alpha.clone();
+        self.blur.apply(&mut blurred, width, height);
+        let (dx, dy) = self.offset;
+        let highlight = shift_plane(&blurred, width, height, (-dx, -dy), 0.0);
+        let shadow = shift_plane(&blurred, width, height, (dx, dy), 0.0);
+
+        for (i, pixel) in bitmap.data.iter_mut().enumerate() {
+            let value = ((highlight[i] - shadow[i]) * self.strength).clamp(-1.0, 1.0);
+            let mask = match self.filter_type {
+                FilterType::Inner => alpha[i],
+                FilterType::Outer => 1.0 - alpha[i],
+                FilterType::Full => 1.0,
+            };
+            let bevel = scale_color(color(value), mask);
+
+            let object = argb_to_floats(*pixel);
+            let out = if self.knockout {
+                bevel
+            } else {
+                over(bevel, object)
+            };
+            *pixel = floats_to_argb(out);
+        }
+    }
+}
+
+/// Get the offset of a shadow, in bitmap pixels.
+fn offset(angle: f64, distance: f64, scale: f64) -> (isize, isize) {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    (
+        (cos * distance * scale).round() as isize,
+        (sin * distance * scale).round() as isize,
+    )
+}
+
+/// Make a premultiplied color, with channels from 0 to 1.
+fn solid((r, g, b): (u8, u8, u8), alpha: f64) -> [f64; 4] {
+    let alpha = alpha.clamp(0.0, 1.0);
+    [
+        alpha,
+        f64::from(r) / 255.0 * alpha,
+        f64::from(g) / 255.0 * alpha,
+        f64::from(b) / 255.0 * alpha,
+    ]
+}
+
+fn scale_color(color: [f64; 4], scale: f64) -> [f64; 4] {
+    let mut ret = color;
+    for channel in ret.iter_mut() {
+        *channel *= scale;
+    }
+
+    ret
+}
+
+/// Composite a premultiplied color over another.
+fn over(top: [f64; 4], bottom: [f64; 4]) -> [f64; 4] {
+    let mut ret = [0.0; 4];
+    for (i, channel) in ret.iter_mut().enumerate() {
+        *channel = top[i] + bottom[i] * (1.0 - top[0]);
+    }
+
+    ret
+}
+
+fn argb_to_floats(pixel: u32) -> [f64; 4] {
+    let mut ret = [0.0; 4];
+    for (channel, byte) in ret.iter_mut().zip(pixel.to_be_bytes().iter()) {
+        *channel = f64::from(*byte) / 255.0;
+    }
+
+    ret
+}
+
+fn floats_to_argb(color: [f64; 4]) -> u32 {
+    let mut bytes = [0; 4];
+    for (byte, channel) in bytes.iter_mut().zip(color.iter()) {
+        *byte = (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+
+    u32::from_be_bytes(bytes)
+}
+
+/// Get the unpremultiplied channels of a pixel, in the order alpha, red, green, blue.
+fn unpremultiply(pixel: u32) -> [f64; 4] {
+    let [a, r, g, b] = pixel.to_be_bytes();
+    if a == 0 {
+        return [0.0; 4];
+    }
+
+    let scale = 255.0 / f64::from(a);
+    [
+        f64::from(a),
+        f64::from(r) * scale,
+        f64::from(g) * scale,
+        f64::from(b) * scale,
+    ]
+}
+
+/// Make a premultiplied pixel from unpremultiplied channels, in the order alpha, red, green, blue.
+fn premultiply([a, r, g, b]: [f64; 4]) -> u32 {
+    let scale = a / 255.0;
+    let channel = |value: f64| (value * scale).round().clamp(0.0, 255.0) as u8;
+    u32::from_be_bytes([a.round() as u8, channel(r), channel(g), channel(b)])
+}
+
+/// Get the alpha of every pixel, from 0 to 1.
+fn alpha_plane(bitmap: &Bitmap) -> Vec<f64> {
+    bitmap
+        .data
+        .iter()
+        .map(|pixel| f64::from(pixel >> 24) / 255.0)
+        .collect()
+}
+
+/// Split premultiplied pixels into one plane per channel.
+fn split_planes(bitmap: &Bitmap) -> [Vec<f64>; 4] {
+    let mut planes = [
+        Vec::with_capacity(bitmap.data.len()),
+        Vec::with_capacity(bitmap.data.len()),
+        Vec::with_capacity(bitmap.data.len()),
+        Vec::with_capacity(bitmap.data.len()),
+    ];
+    for pixel in bitmap.data.iter() {
+        for (plane, byte) in planes.iter_mut().zip(pixel.to_be_bytes().iter()) {
+            plane.push(f64::from(*byte));
+        }
+    }
+
+    planes
+}
+
+fn join_planes(bitmap: &mut Bitmap, planes: &[Vec<f64>; 4]) {
+    for (i, pixel) in bitmap.data.iter_mut().enumerate() {
+        let mut bytes = [0; 4];
+        for (byte, plane) in bytes.iter_mut().zip(planes.iter()) {
+            *byte = plane[i].round().clamp(0.0, 255.0) as u8;
+        }
+
+        // Keep the pixel premultiplied
+        let alpha = bytes[0];
+        for byte in bytes[1..].iter_mut() {
+            *byte = (*byte).min(alpha);
+        }
+        *pixel = u32::from_be_bytes(bytes);
+    }
+}
+
+/// Move a plane by an offset. Uncovered values are set to `fill`.
+fn shift_plane(
+    plane: &[f64],
+    width: usize,
+    height: usize,
+    (dx, dy): (isize, isize),
+    fill: f64,
+) -> Vec<f64> {
+    let mut ret = vec![fill; plane.len()];
+    for y in 0..height {
+        let src_y = y as isize - dy;
+        if src_y < 0 || src_y >= height as isize {
+            continue;
+        }
+
+        for x in 0..width {
+            let src_x = x as isize - dx;
+            if src_x < 0 || src_x >= width as isize {
+                continue;
+            }
+
+            ret[y * width + x] = plane[src_y as usize * width + src_x as usize];
+        }
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::filter::GradientEntry;
+
+    #[test]
+    fn sample_gradient() {
+        let filter = GradientGlowFilter {
+            blur_x: 5.0,
+            blur_y: 5.0,
+            quality: 1,
+            strength: 1.0,
+            angle: 45.0,
+            distance: 5.0,
+            knockout: false,
+            filter_type: Some(FilterType::Outer),
+            gradient_entries: vec![
+                GradientEntry {
+                    color: (0xFF, 0xFF, 0xFF),
+                    alpha: 0.0,
+                    ratio: 0.0,
+                },
+                GradientEntry {
+                    color: (0xFF, 0, 0),
+                    alpha: 1.0,
+                    ratio: 1.0,
+                },
+            ],
+        };
+
+        assert_eq!(filter.sample_gradient(1.0), [1.0, 1.0, 0.0, 0.0]);
+        assert_eq!(filter.sample_gradient(0.5), [0.5, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn blur_spreads_pixels() {
+        let mut bitmap = Bitmap {
+            width: 5,
+            height: 1,
+            data: vec![0, 0, 0xFF_FF_00_00, 0, 0],
+        };
+        let filter = BlurFilter {
+            blur_x: 3.0,
+            blur_y: 0.0,
+            quality: 1,
+        };
+        filter.apply(&mut bitmap, 1.0);
+
+        assert_eq!(
+            bitmap.data,
+            [0, 0x55_55_00_00, 0x55_55_00_00, 0x55_55_00_00, 0]
+        );
+    }
+
+    #[test]
+    fn desaturate() {
+        let filter = AdjustColorFilter {
+            brightness: 0.0,
+            contrast: 0.0,
+            saturation: -100.0,
+            hue: 0.0,
+        };
+        let mut bitmap = Bitmap {
+            width: 1,
+            height: 1,
+            data: vec![0xFF_FF_00_00],
+        };
+        filter.apply(&mut bitmap);
+
+        let [a, r, g, b] = bitmap.data[0].to_be_bytes();
+        assert_eq!(a, 0xFF);
+        assert!(r == g && g == b);
+    }
+}
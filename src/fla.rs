@@ -1,24 +1,56 @@
 use crate::{
+    bitmap::{
+        Bitmap,
+        BitmapError,
+    },
+    text::{
+        Font,
+        FontError,
+        FontLibrary,
+    },
     types::{
+        action_script::FrameScript,
+        dom_timeline::LayerPolicy,
+        DomBitmapItem,
         DomDocument,
+        DomFontItem,
+        DomSymbol,
         LibraryEntry,
     },
     FlaResult,
 };
 use std::{
+    cell::RefCell,
     collections::HashMap,
     io::{
         Read,
         Seek,
     },
 };
-use zip::ZipArchive;
+use zip::{
+    result::ZipError,
+    ZipArchive,
+};
+
+/// A bounding box, in twips.
+type BoundingBox = euclid::default::Box2D<f64>;
 
 /// An Fla struct.
 #[derive(Debug)]
 pub struct Fla {
     pub dom_document: DomDocument,
     pub library: HashMap<String, LibraryEntry>,
+
+    /// The undecoded data of bitmaps, by bitmap item name.
+    pub bitmap_data: HashMap<String, Vec<u8>>,
+
+    /// The source of the `.as` files stored in the fla, by path.
+    ///
+    /// Included files outside of the fla are not read, see [`Fla::get_unresolved_script_includes`].
+    pub script_files: HashMap<String, String>,
+
+    /// The bounding boxes of symbols, by symbol name and layer policy, computed as they are needed.
+    bounding_boxes: RefCell<HashMap<(String, LayerPolicy), Option<BoundingBox>>>,
 }
 
 impl Fla {
@@ -36,9 +68,42 @@ impl Fla {
             library.insert(name, entry);
         }
 
+        let mut bitmap_data = HashMap::new();
+        for bitmap_item in dom_document.media.get_bitmap_items() {
+            let href = match bitmap_item.bitmap_data_href.as_ref() {
+                Some(href) => href,
+                None => continue,
+            };
+
+            // Bitmaps without data are reported when they are decoded.
+            let mut file = match zip.by_name(&format!("bin/{}", href)) {
+                Ok(file) => file,
+                Err(ZipError::FileNotFound) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)?;
+            bitmap_data.insert(bitmap_item.name.clone(), data);
+        }
+
+        let script_paths: Vec<String> = zip
+            .file_names()
+            .filter(|name| name.ends_with(".as"))
+            .map(|name| name.to_string())
+            .collect();
+        let mut script_files = HashMap::with_capacity(script_paths.len());
+        for path in script_paths {
+            let mut source = String::new();
+            zip.by_name(&path)?.read_to_string(&mut source)?;
+            script_files.insert(path, source);
+        }
+
         Ok(Fla {
             dom_document,
             library,
+            bitmap_data,
+            script_files,
+            bounding_boxes: RefCell::new(HashMap::new()),
         })
     }
 
@@ -46,4 +111,262 @@ impl Fla {
     pub fn get_library_asset(&self, filename: &str) -> Option<&LibraryEntry> {
         self.library.get(filename)
     }
+
+    /// Get a bitmap item from the media library by its item name, like `libraryItemName`.
+    pub fn get_bitmap_item(&self, name: &str) -> Option<&DomBitmapItem> {
+        self.dom_document
+            .media
+            .get_bitmap_items()
+            .find(|bitmap_item| bitmap_item.name == name)
+    }
+
+    /// Decode a bitmap by its item name.
+    ///
+    /// Returns `None` if the fla does not have data for that bitmap.
+    pub fn decode_bitmap(&self, name: &str) -> Option<Result<Bitmap, BitmapError>> {
+        Some(Bitmap::from_dat(self.bitmap_data.get(name)?))
+    }
+
+    /// Get a symbol from the library by its item name, like `libraryItemName`.
+    pub fn get_symbol(&self, name: &str) -> Option<&DomSymbol> {
+        self.get_library_asset(&format!("{}.xml", name))
+            .and_then(|entry| entry.as_xml())
+            .filter(|symbol| symbol.name == name)
+            .or_else(|| {
+                self.library
+                    .values()
+                    .filter_map(|entry| entry.as_xml())
+                    .find(|symbol| symbol.name == name)
+            })
+    }
+
+    /// Get the bounding box of a library symbol in twips, like [`DomSymbol::calc_bounding_box`].
+    ///
+    /// The bounding box is computed the first time it is needed for a layer policy.
+    pub fn get_symbol_bounding_box(
+        &self,
+        symbol: &DomSymbol,
+        layer_policy: &LayerPolicy,
+    ) -> Option<BoundingBox> {
+        let key = (symbol.name.clone(), *layer_policy);
+        if let Some(bounding_box) = self.bounding_boxes.borrow().get(&key) {
+            return *bounding_box;
+        }
+
+        let bounding_box = symbol.calc_bounding_box(self, layer_policy);
+        self.bounding_boxes.borrow_mut().insert(key, bounding_box);
+
+        bounding_box
+    }
+
+    /// Get the source of an `.as` file in the fla by its path, like an `#include` or a document script.
+    pub fn get_script_file(&self, path: &str) -> Option<&str> {
+        self.script_files
+            .get(path)
+            .or_else(|| self.script_files.get(path.trim_start_matches("./")))
+            .map(|source| source.as_str())
+    }
+
+    /// Get the scripts of every keyframe in this fla, with their symbol, layer and frame.
+    ///
+    /// Scripts of library symbols come first, ordered by symbol name, followed by the scenes.
+    pub fn get_scripts(&self) -> Vec<FrameScript<'_>> {
+        let mut symbols: Vec<_> = self
+            .library
+            .values()
+            .filter_map(|entry| entry.as_xml())
+            .collect();
+        symbols.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut ret = Vec::new();
+        for symbol in symbols {
+            ret.extend(
+                symbol
+                    .timeline
+                    .dom_timeline
+                    .get_scripts()
+                    .into_iter()
+                    .map(|script| FrameScript {
+                        symbol: Some(&symbol.name),
+                        ..script
+                    }),
+            );
+        }
+        for timeline in self.dom_document.timelines.dom_timelines.iter() {
+            ret.extend(timeline.get_scripts());
+        }
+
+        ret
+    }
+
+    /// Get the paths of the `.as` files included by the document and by `#include`s in frame scripts, without duplicates.
+    pub fn get_script_includes(&self) -> Vec<&str> {
+        let frame_includes: Vec<_> = self
+            .get_scripts()
+            .iter()
+            .flat_map(|script| script.get_includes())
+            .collect();
+
+        let mut ret = Vec::new();
+        for path in self
+            .dom_document
+            .scripts
+            .get_includes()
+            .chain(frame_includes)
+        {
+            if !ret.contains(&path) {
+                ret.push(path);
+            }
+        }
+
+        ret
+    }
+
+    /// Get the paths of included `.as` files that are not stored in the fla, see [`Fla::get_script_includes`].
+    ///
+    /// Included files are usually kept next to the fla instead, so they have to be read relative to it.
+    pub fn get_unresolved_script_includes(&self) -> Vec<&str> {
+        self.get_script_includes()
+            .into_iter()
+            .filter(|path| self.get_script_file(path).is_none())
+            .collect()
+    }
+
+    /// Get a font item by its item name.
+    pub fn get_font_item(&self, name: &str) -> Option<&DomFontItem> {
+        self.dom_document
+            .fonts
+            .dom_font_items
+            .iter()
+            .find(|font_item| font_item.name == name)
+    }
+
+    /// Get the font for a text face, without using the fallback font.
+    ///
+    /// Faces naming a font item, like `Font 1*`, use the font of that item.
+    pub fn find_font<'b>(&self, font_library: &'b FontLibrary, face: &str) -> Option<&'b Font> {
+        font_library.get(face).or_else(|| {
+            let font_item = self.get_font_item(face.trim_end_matches('*'))?;
+            font_library.get(&font_item.font)
+        })
+    }
+
+    /// Get the font for a text face, using the fallback font if it is missing.
+    ///
+    /// Text without a face always uses the fallback font.
+    pub fn resolve_font<'b>(
+        &self,
+        font_library: &'b FontLibrary,
+        face: Option<&str>,
+    ) -> Result<&'b Font, FontError> {
+        face.and_then(|face| self.find_font(font_library, face))
+            .or_else(|| font_library.get_fallback())
+            .ok_or_else(|| FontError::MissingFont(face.unwrap_or_default().to_string()))
+    }
+
+    /// Get the faces of text in this fla that do not have a registered font.
+    ///
+    /// Text with these faces is drawn with the fallback font, if there is one.
+    pub fn get_missing_fonts(&self, font_library: &FontLibrary) -> Vec<String> {
+        let timelines = self
+            .library
+            .values()
+            .filter_map(|entry| entry.as_xml())
+            .map(|symbol| &symbol.timeline.dom_timeline)
+            .chain(self.dom_document.timelines.dom_timelines.iter());
+
+        let mut ret = Vec::new();
+        for timeline in timelines {
+            let texts = timeline
+                .get_layers()
+                .iter()
+                .flat_map(|layer| layer.get_frames())
+                .flat_map(|frame| frame.get_texts());
+            for text_run in texts.flat_map(|text| text.get_text_runs()) {
+                let face = match text_run.get_text_attrs().get_face() {
+                    Some(face) => face.to_string(),
+                    None => continue,
+                };
+                if self.find_font(font_library, &face).is_none() && !ret.contains(&face) {
+                    ret.push(face);
+                }
+            }
+        }
+        ret.sort();
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        dot,
+        layer,
+        make_fla,
+        symbol,
+    };
+
+    #[test]
+    fn missing_bitmap_data() {
+        let fla = make_fla(
+            &[],
+            r#"<DOMBitmapItem name="pic.png" bitmapDataHRef="M 1 1.dat" frameRight="40" frameBottom="40"/>"#,
+            &[],
+        );
+        assert!(fla.get_bitmap_item("pic.png").is_some());
+        assert!(fla.decode_bitmap("pic.png").is_none());
+    }
+
+    #[test]
+    fn scripts() {
+        let actions = layer(
+            "",
+            r#"<DOMFrame index="0" keyMode="9728"><Actionscript><script><![CDATA[#include "lib/utils.as"
+#include "./lib/missing.as"
+if (a < b) { stop(); }]]></script></Actionscript><elements/></DOMFrame>"#,
+        );
+        let fla = make_fla(
+            &[("Main", symbol("Main", "", &actions))],
+            "",
+            &[("lib/utils.as", b"function f() {}")],
+        );
+
+        let scripts = fla.get_scripts();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].symbol, Some("Main"));
+        assert_eq!(scripts[0].layer_name, "layer");
+        assert_eq!(scripts[0].frame_index, 0);
+        assert!(scripts[0].source.ends_with("if (a < b) { stop(); }"));
+
+        assert_eq!(
+            fla.get_script_includes(),
+            ["lib/utils.as", "./lib/missing.as"]
+        );
+        assert_eq!(fla.get_script_file("lib/utils.as"), Some("function f() {}"));
+        assert_eq!(fla.get_unresolved_script_includes(), ["./lib/missing.as"]);
+    }
+
+    #[test]
+    fn symbol_bounding_boxes_are_cached() {
+        let fla = make_fla(&[dot()], "", &[]);
+        let symbol = fla.get_symbol("Dot").unwrap();
+        let layer_policy = LayerPolicy::default();
+
+        let bounding_box = fla.get_symbol_bounding_box(symbol, &layer_policy);
+        assert_eq!(bounding_box, symbol.calc_bounding_box(&fla, &layer_policy));
+        assert_eq!(
+            fla.get_symbol_bounding_box(symbol, &layer_policy),
+            bounding_box
+        );
+        assert_eq!(fla.bounding_boxes.borrow().len(), 1);
+
+        let layer_policy = LayerPolicy {
+            include_guides: true,
+            ..layer_policy
+        };
+        fla.get_symbol_bounding_box(symbol, &layer_policy);
+        assert_eq!(fla.bounding_boxes.borrow().len(), 2);
+    }
 }
@@ -1,7 +1,24 @@
+pub mod bitmap;
+pub mod display_list;
+pub mod filter;
 pub mod fla;
+pub mod player;
+pub mod render;
+pub mod sampler;
+pub mod text;
+pub mod tween;
 pub mod types;
+mod xml;
 
-pub use crate::fla::Fla;
+#[cfg(test)]
+mod test_util;
+
+pub use crate::{
+    display_list::DisplayList,
+    fla::Fla,
+    player::Player,
+    sampler::TimelineSampler,
+};
 
 /// Result type
 pub type FlaResult<T> = Result<T, FlaError>;
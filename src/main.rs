@@ -1,4 +1,9 @@
-use fla::Fla;
+use fla::{
+    render::RenderOptions,
+    text::Font,
+    types::dom_timeline::LayerPolicy,
+    Fla,
+};
 use std::{
     fs::File,
     path::{
@@ -26,6 +31,39 @@ struct FlaCmd {
     #[argh(option)]
     #[argh(description = "the padding of the symbol")]
     padding: Option<f64>,
+
+    #[argh(option)]
+    #[argh(description = "the number of ticks to render, defaulting to the length of the symbol")]
+    ticks: Option<usize>,
+
+    #[argh(option)]
+    #[argh(description = "render the frames of a frame label instead of playing the symbol")]
+    label: Option<String>,
+
+    #[argh(switch)]
+    #[argh(description = "render guide layers")]
+    include_guides: bool,
+
+    #[argh(switch)]
+    #[argh(description = "skip layers hidden in the editor")]
+    skip_hidden: bool,
+
+    #[argh(switch)]
+    #[argh(description = "also save the hit area of a button symbol as a mask")]
+    hit_area: bool,
+
+    #[argh(option)]
+    #[argh(description = "a font file used to render text, which may be passed multiple times")]
+    font: Vec<PathBuf>,
+
+    #[argh(option)]
+    #[argh(description = "the font file used to render text whose font is missing")]
+    fallback_font: Option<PathBuf>,
+}
+
+fn load_font(path: &Path) -> Result<Font, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    Font::new(data).map_err(|e| e.to_string())
 }
 
 fn main() {
@@ -50,6 +88,48 @@ fn main() {
         }
     };
 
+    let mut options = RenderOptions::default();
+    for path in fla_cmd.font.iter() {
+        match load_font(path) {
+            Ok(font) => options.font_library.register(font),
+            Err(e) => {
+                eprintln!("Failed to load font '{}': {}", path.display(), e);
+                return;
+            }
+        }
+    }
+
+    options.layer_policy = LayerPolicy {
+        include_guides: fla_cmd.include_guides,
+        include_hidden: !fla_cmd.skip_hidden,
+    };
+
+    if let Some(path) = fla_cmd.fallback_font.as_ref() {
+        match load_font(path) {
+            Ok(font) => options.font_library.set_fallback(font),
+            Err(e) => {
+                eprintln!("Failed to load fallback font '{}': {}", path.display(), e);
+                return;
+            }
+        }
+    }
+
+    let missing_fonts = parsed_fla_file.get_missing_fonts(&options.font_library);
+    if !missing_fonts.is_empty() {
+        let has_fallback = options.font_library.get_fallback().is_some();
+        for face in missing_fonts.iter() {
+            if has_fallback {
+                println!("Missing font '{}', using the fallback font", face);
+            } else {
+                println!(
+                    "Missing font '{}', pass it with --font or pass --fallback-font",
+                    face
+                );
+            }
+        }
+        println!();
+    }
+
     let symbol = match parsed_fla_file
         .get_library_asset(&fla_cmd.symbol)
         .and_then(|s| s.as_xml())
@@ -63,22 +143,45 @@ fn main() {
 
     let scale = fla_cmd.scale.unwrap_or(1.0);
     let padding = fla_cmd.padding.unwrap_or(20.0 * scale);
+    let ticks = fla_cmd.ticks.unwrap_or_else(|| symbol.num_frames());
 
-    if let Some(bounding_box) = symbol.calc_bounding_box() {
+    if let Some(bounding_box) = symbol.calc_bounding_box(&parsed_fla_file, &options.layer_policy) {
         println!("Symbol Bounding Box");
         println!("  Start: {} x {}", bounding_box.min.x, bounding_box.min.y);
         println!("  End: {} x {}", bounding_box.max.x, bounding_box.max.y);
         println!();
     }
 
+    let labels = symbol.get_labels();
+    if !labels.is_empty() {
+        println!("Labels");
+        for label in labels.iter() {
+            println!(
+                "  {} ({:?}): frame {}",
+                label.name, label.label_type, label.frame_index
+            );
+        }
+        println!();
+    }
+
     println!("Using scale: {}x", scale);
     println!("Using padding: {}px", padding);
+    match fla_cmd.label.as_deref() {
+        Some(label) => println!("Using label: {}", label),
+        None => println!("Using ticks: {}", ticks),
+    }
     println!();
 
     println!("Beginning render...");
     let render_start = Instant::now();
 
-    let frames = match symbol.render_raqote(scale, padding) {
+    let result = match fla_cmd.label.as_deref() {
+        Some(label) => {
+            symbol.render_raqote_label(&parsed_fla_file, &options, scale, padding, label)
+        }
+        None => symbol.render_raqote_ticks(&parsed_fla_file, &options, scale, padding, ticks),
+    };
+    let frames = match result {
         Ok(frames) => frames,
         Err(e) => {
             eprintln!("Failed to render: {}", e);
@@ -112,5 +215,20 @@ fn main() {
         export_path.pop();
     }
 
+    if fla_cmd.hit_area {
+        export_path.push("hit.png");
+        println!("Saving hit area in '{}'", export_path.display());
+
+        let result = symbol
+            .render_raqote_hit_area(&parsed_fla_file, &options, scale, padding)
+            .map_err(|e| e.to_string())
+            .and_then(|hit_area| hit_area.write_png(&export_path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to save hit area: {}", e);
+        }
+
+        export_path.pop();
+    }
+
     println!("Done.");
 }
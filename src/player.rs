@@ -0,0 +1,344 @@
+use crate::{
+    sampler::{
+        check_symbol_depth,
+        SampleError,
+        SampledElement,
+        SampledElementKind,
+        TimelineSampler,
+    },
+    types::{
+        dom_symbol_instance::SymbolType,
+        dom_timeline::LayerPolicy,
+        DomSymbol,
+    },
+    Fla,
+};
+use std::collections::HashMap;
+
+/// Plays a symbol tick by tick, like the Flash Player.
+///
+/// Unlike graphics, every movie clip instance has its own playhead.
+/// It starts at the first frame when the instance is placed and advances once per tick,
+/// independently of the timeline holding it. Buttons stay in their up state.
+#[derive(Debug)]
+pub struct Player<'a> {
+    sampler: TimelineSampler<'a>,
+    symbol: &'a DomSymbol,
+    playhead: Playhead,
+}
+
+impl<'a> Player<'a> {
+    /// Make a new player for a symbol, starting at its first frame.
+    ///
+    /// Layers are skipped according to the layer policy, like when rendering.
+    pub fn new(
+        fla: &'a Fla,
+        symbol: &'a DomSymbol,
+        layer_policy: LayerPolicy,
+    ) -> Result<Self, SampleError> {
+        let mut player = Self {
+            sampler: TimelineSampler::new(fla, layer_policy),
+            symbol,
+            playhead: Playhead::default(),
+        };
+        player.update(false)?;
+
+        Ok(player)
+    }
+
+    /// Get the symbol being played.
+    pub fn get_symbol(&self) -> &'a DomSymbol {
+        self.symbol
+    }
+
+    /// Get the policy deciding which layers are played.
+    pub fn get_layer_policy(&self) -> &LayerPolicy {
+        self.sampler.get_layer_policy()
+    }
+
+    /// Get the playhead of the symbol being played.
+    pub fn get_playhead(&self) -> &Playhead {
+        &self.playhead
+    }
+
+    /// Get the current frame of the symbol being played.
+    pub fn get_frame_index(&self) -> usize {
+        self.playhead.frame_index
+    }
+
+    /// Advance every playhead by one frame.
+    pub fn tick(&mut self) -> Result<(), SampleError> {
+        self.update(true)
+    }
+
+    fn update(&mut self, advance: bool) -> Result<(), SampleError> {
+        update_playhead(
+            &self.sampler,
+            &mut self.playhead,
+            self.symbol,
+            advance,
+            true,
+            0,
+        )
+    }
+}
+
+/// The playback state of a timeline.
+#[derive(Debug, Default, Clone)]
+pub struct Playhead {
+    frame_index: usize,
+    children: HashMap<InstanceKey, Playhead>,
+}
+
+impl Playhead {
+    /// Get the current frame, 0-indexed.
+    pub fn get_frame_index(&self) -> usize {
+        self.frame_index
+    }
+
+    /// Get the playhead of a symbol instance sampled from the timeline of this playhead.
+    pub fn get_child(&self, sampled: &SampledElement) -> Option<&Playhead> {
+        self.children.get(&InstanceKey::new(sampled)?)
+    }
+}
+
+/// Identifies a symbol instance across frames.
+///
+/// Instances of the same symbol at the same place in a layer are treated as the same instance,
+/// so movie clips keep playing across keyframes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct InstanceKey {
+    layer_index: usize,
+    element_index: usize,
+    library_item_name: String,
+}
+
+impl InstanceKey {
+    fn new(sampled: &SampledElement) -> Option<Self> {
+        match sampled.kind {
+            SampledElementKind::Symbol {
+                symbol_instance, ..
+            } => Some(Self {
+                layer_index: sampled.layer_index,
+                element_index: sampled.element_index,
+                library_item_name: symbol_instance.library_item_name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Update a playhead and the playheads of the instances on its timeline.
+///
+/// Only playheads that own their frame are advanced; graphics and buttons follow the timeline holding them.
+/// Instances that are newly placed start without being advanced.
+/// The depth is the number of symbols the symbol is nested in.
+fn update_playhead(
+    sampler: &TimelineSampler,
+    playhead: &mut Playhead,
+    symbol: &DomSymbol,
+    advance: bool,
+    owns_frame: bool,
+    depth: usize,
+) -> Result<(), SampleError> {
+    check_symbol_depth(symbol, depth)?;
+
+    if advance && owns_frame {
+        playhead.frame_index = (playhead.frame_index + 1) % symbol.num_frames().max(1);
+    }
+
+    let mut old_children = std::mem::take(&mut playhead.children);
+    for sampled in sampler.sample_symbol(symbol, playhead.frame_index)? {
+        let key = match InstanceKey::new(&sampled) {
+            Some(key) => key,
+            None => continue,
+        };
+
+        let (symbol_instance, symbol, frame_index) = match sampled.kind {
+            SampledElementKind::Symbol {
+                symbol_instance,
+                symbol,
+                frame_index,
+            } => (symbol_instance, symbol, frame_index),
+            _ => continue,
+        };
+
+        let (mut child, advance_child) = match old_children.remove(&key) {
+            Some(child) => (child, advance),
+            None => (Playhead::default(), false),
+        };
+
+        let owns_frame = symbol_instance.get_symbol_type() == SymbolType::MovieClip;
+        if !owns_frame {
+            child.frame_index = frame_index;
+        }
+
+        update_playhead(
+            sampler,
+            &mut child,
+            symbol,
+            advance_child,
+            owns_frame,
+            depth + 1,
+        )?;
+        playhead.children.insert(key, child);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        frame,
+        layer,
+        make_fla,
+        square,
+        symbol,
+    };
+
+    /// A movie clip with four keyframes.
+    fn clip() -> (&'static str, String) {
+        let frames: String = (0..4)
+            .map(|i| frame(i, "", &square("#FF0000", 10)))
+            .collect();
+        ("Clip", symbol("Clip", "", &layer("", &frames)))
+    }
+
+    const CLIP_INSTANCE: &str =
+        r#"<DOMSymbolInstance libraryItemName="Clip" symbolType="movie clip"/>"#;
+
+    /// Play a symbol, collecting its frame and the frames of its instances at each tick.
+    fn play(fla: &Fla, name: &str, num_ticks: usize) -> Vec<(usize, Vec<usize>)> {
+        let mut player =
+            Player::new(fla, fla.get_symbol(name).unwrap(), LayerPolicy::default()).unwrap();
+        let sampler = TimelineSampler::new(fla, LayerPolicy::default());
+
+        let mut ret = Vec::with_capacity(num_ticks);
+        for _ in 0..num_ticks {
+            let children = sampler
+                .sample_symbol(player.get_symbol(), player.get_frame_index())
+                .unwrap()
+                .iter()
+                .filter_map(|sampled| player.get_playhead().get_child(sampled))
+                .map(|child| child.get_frame_index())
+                .collect();
+            ret.push((player.get_frame_index(), children));
+            player.tick().unwrap();
+        }
+
+        ret
+    }
+
+    #[test]
+    fn movie_clip_in_stopped_parent() {
+        let fla = make_fla(
+            &[
+                clip(),
+                (
+                    "Main",
+                    symbol("Main", "", &layer("", &frame(0, "", CLIP_INSTANCE))),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        let children: Vec<_> = play(&fla, "Main", 6)
+            .into_iter()
+            .map(|(frame_index, children)| (frame_index, children[0]))
+            .collect();
+        assert_eq!(children, [(0, 0), (0, 1), (0, 2), (0, 3), (0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn movie_clip_in_looping_parent() {
+        let fla = make_fla(
+            &[
+                clip(),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer("", &frame(0, r#"duration="3""#, CLIP_INSTANCE)),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The parent loops back to its first frame, but the instance is still placed, so it keeps playing.
+        let children: Vec<_> = play(&fla, "Main", 6)
+            .into_iter()
+            .map(|(frame_index, children)| (frame_index, children[0]))
+            .collect();
+        assert_eq!(children, [(0, 0), (1, 1), (2, 2), (0, 3), (1, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn movie_clip_across_keyframes() {
+        let fla = make_fla(
+            &[
+                clip(),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &(frame(0, r#"duration="2""#, CLIP_INSTANCE)
+                                + &frame(2, r#"duration="2""#, CLIP_INSTANCE)
+                                + &frame(4, "", "")
+                                + &frame(5, r#"duration="3""#, CLIP_INSTANCE)),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // Keyframes keeping the instance keep its playhead, and placing it again restarts it.
+        let frames = play(&fla, "Main", 8);
+        assert_eq!(
+            frames,
+            [
+                (0, vec![0]),
+                (1, vec![1]),
+                (2, vec![2]),
+                (3, vec![3]),
+                (4, vec![]),
+                (5, vec![0]),
+                (6, vec![1]),
+                (7, vec![2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn recursion_limit() {
+        let elements = format!(
+            r#"{}<DOMSymbolInstance libraryItemName="Loop" symbolType="movie clip"/>"#,
+            square("#FF0000", 10)
+        );
+        let fla = make_fla(
+            &[(
+                "Loop",
+                symbol("Loop", "", &layer("", &frame(0, "", &elements))),
+            )],
+            "",
+            &[],
+        );
+
+        let result = Player::new(
+            &fla,
+            fla.get_symbol("Loop").unwrap(),
+            LayerPolicy::default(),
+        );
+        assert!(matches!(result, Err(SampleError::RecursionLimit(name)) if name == "Loop"));
+    }
+}
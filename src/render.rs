@@ -0,0 +1,820 @@
+#[cfg(feature = "render-raqote")]
+pub mod raqote;
+
+use crate::{
+    bitmap::Bitmap,
+    display_list::{
+        DisplayItem,
+        DisplayList,
+        DisplayScope,
+        Primitive,
+    },
+    player::Playhead,
+    text::{
+        layout_text,
+        FontLibrary,
+        PathCommand,
+    },
+    types::{
+        dom_shape::{
+            EdgeDefinitionCommand,
+            SelectionMask,
+        },
+        dom_symbol::DomSymbolRenderError,
+        dom_symbol_instance::BlendMode,
+        dom_timeline::LayerPolicy,
+        matrix::TWIPS_PER_PIXEL,
+        ColorTransform,
+        DomBitmapItem,
+        DomShape,
+        DomSymbol,
+        DomText,
+        Filter,
+    },
+    Fla,
+};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// A rendering backend.
+///
+/// Paths are in pixels of the render target. Everything is drawn to the top layer,
+/// which is the render target itself until a layer is pushed.
+///
+/// There is no clip primitive, as masks are not paths: mask layers can hold bitmaps, text and filtered instances,
+/// and their alpha fades what they mask. Masks are drawn into a layer instead, see `pop_mask`.
+pub trait Renderer {
+    /// Fill a path with the non-zero winding rule.
+    fn fill_path(&mut self, path: &[PathCommand], color: Color);
+
+    /// Stroke a path with round caps and joins. The width is in pixels.
+    fn stroke_path(&mut self, path: &[PathCommand], color: Color, width: f64);
+
+    /// Draw a bitmap, with `transform` mapping its pixels to pixels of the render target.
+    ///
+    /// Bitmaps are smoothed when they are scaled if `smooth` is set.
+    fn draw_bitmap(
+        &mut self,
+        bitmap: &Bitmap,
+        transform: &euclid::default::Transform2D<f64>,
+        smooth: bool,
+    );
+
+    /// Push a new, transparent layer the size of the render target.
+    fn push_layer(&mut self);
+
+    /// Pop the top layer and draw it onto the layer below it with effects.
+    fn pop_layer(&mut self, effects: &LayerEffects);
+
+    /// Pop the top layer and use it as a mask for the layer below it.
+    ///
+    /// Pixels of the layer below are scaled by the alpha of the mask.
+    fn pop_mask(&mut self);
+}
+
+/// An unpremultiplied color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Make a color, applying a color transform.
+    pub fn transformed(color_transform: &ColorTransform, (r, g, b): (u8, u8, u8), a: u8) -> Self {
+        let [r, g, b, a] = color_transform.apply([r, g, b, a]);
+        Self { r, g, b, a }
+    }
+}
+
+/// How a layer is drawn onto the layer below it.
+///
+/// Filters are applied first, and the color transform applies to the filtered result.
+#[derive(Debug, Clone, Copy)]
+pub struct LayerEffects<'a> {
+    pub filters: &'a [Filter],
+
+    /// The number of pixels of the render target in a pixel of the rendered symbol, which scales filters.
+    pub filter_scale: f64,
+
+    pub color_transform: ColorTransform,
+    pub blend_mode: BlendMode,
+}
+
+impl Default for LayerEffects<'_> {
+    fn default() -> Self {
+        Self {
+            filters: &[],
+            filter_scale: 1.0,
+            color_transform: ColorTransform::IDENTITY,
+            blend_mode: BlendMode::Normal,
+        }
+    }
+}
+
+/// Options for rendering an fla, which are not part of the fla itself.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// The fonts used to render text.
+    pub font_library: FontLibrary,
+
+    /// Which layers are rendered.
+    pub layer_policy: LayerPolicy,
+}
+
+/// The size of a render target and the placement of a symbol in it.
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+    pub width: i32,
+    pub height: i32,
+
+    /// Maps the symbol's twips to pixels of the render target.
+    pub transform: euclid::default::Transform2D<f64>,
+
+    /// The number of pixels of the render target in a twip.
+    pub scale: f64,
+}
+
+impl View {
+    /// Make a view fitting the bounding box of a symbol, scaled by `scale` and padded by `padding` pixels.
+    ///
+    /// Layers skipped by the layer policy do not count towards the bounding box.
+    /// Filters of instances, like blurs and drop shadows, grow the bounding box by how far they draw outside of their content.
+    pub fn fit_symbol(
+        symbol: &DomSymbol,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        scale: f64,
+        padding: f64,
+    ) -> Result<Self, DomSymbolRenderError> {
+        let bounding_box = symbol
+            .calc_render_bounding_box(fla, layer_policy)
+            .ok_or(DomSymbolRenderError::NoBoundingBox)?;
+
+        Ok(Self {
+            width: (bounding_box.width() * scale) as i32 + padding as i32,
+            height: (bounding_box.height() * scale) as i32 + padding as i32,
+            transform: euclid::default::Transform2D::scale(scale, scale).then_translate(
+                euclid::vec2(
+                    -bounding_box.min.x * scale + padding / 2.0,
+                    -bounding_box.min.y * scale + padding / 2.0,
+                ),
+            ),
+            scale,
+        })
+    }
+}
+
+/// Renders the timelines of an fla with a rendering backend.
+///
+/// Timelines are resolved into a display list, so backends only draw paths, bitmaps and layers.
+#[derive(Debug)]
+pub struct TimelineRenderer<'a> {
+    fla: &'a Fla,
+    font_library: &'a FontLibrary,
+    layer_policy: LayerPolicy,
+    bitmaps: RefCell<HashMap<String, Rc<Bitmap>>>,
+    scale: f64,
+
+    /// Maps the rendered symbol's twips to pixels of the render target.
+    view_transform: euclid::default::Transform2D<f64>,
+}
+
+impl<'a> TimelineRenderer<'a> {
+    /// Make a renderer drawing symbols in a view.
+    pub fn new(fla: &'a Fla, view: &View, options: &'a RenderOptions) -> Self {
+        Self {
+            fla,
+            font_library: &options.font_library,
+            layer_policy: options.layer_policy,
+            bitmaps: RefCell::new(HashMap::new()),
+            scale: view.scale,
+            view_transform: view.transform,
+        }
+    }
+
+    /// Render a frame of a symbol, 0-indexed.
+    ///
+    /// If a playhead is given, movie clips show the frames of their own playheads.
+    pub fn render_symbol<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        symbol: &'a DomSymbol,
+        frame_index: usize,
+        playhead: Option<&Playhead>,
+    ) -> Result<(), DomSymbolRenderError> {
+        let display_list = DisplayList::from_playhead(
+            self.fla,
+            &symbol.timeline.dom_timeline,
+            frame_index,
+            playhead,
+            self.layer_policy,
+        )?;
+
+        self.render_display_list(renderer, &display_list)
+    }
+
+    /// Render a display list.
+    pub fn render_display_list<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        display_list: &DisplayList,
+    ) -> Result<(), DomSymbolRenderError> {
+        self.render_items(renderer, display_list, &display_list.items)
+    }
+
+    /// Render primitives, drawing each scope holding them in its own layer.
+    ///
+    /// Consecutive primitives in the same scope share its layer.
+    fn render_items<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        display_list: &DisplayList,
+        items: &[DisplayItem],
+    ) -> Result<(), DomSymbolRenderError> {
+        let mut open_scopes: Vec<usize> = Vec::new();
+        for item in items.iter() {
+            let num_common = open_scopes
+                .iter()
+                .zip(item.scopes.iter())
+                .take_while(|(open_scope, scope)| open_scope == scope)
+                .count();
+            while open_scopes.len() > num_common {
+                let scope = open_scopes.pop().expect("scopes are open");
+                self.close_scope(renderer, display_list, scope)?;
+            }
+            for scope in item.scopes[num_common..].iter() {
+                renderer.push_layer();
+                open_scopes.push(*scope);
+            }
+
+            self.render_item(renderer, item)?;
+        }
+        while let Some(scope) = open_scopes.pop() {
+            self.close_scope(renderer, display_list, scope)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pop the layer of a scope, applying its mask or effects.
+    fn close_scope<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        display_list: &DisplayList,
+        scope: usize,
+    ) -> Result<(), DomSymbolRenderError> {
+        match display_list.scopes.get(scope) {
+            Some(DisplayScope::Mask(mask_index)) => {
+                renderer.push_layer();
+                if let Some(mask) = display_list.masks.get(*mask_index) {
+                    self.render_items(renderer, display_list, &mask.items)?;
+                }
+                renderer.pop_mask();
+                renderer.pop_layer(&LayerEffects::default());
+            }
+            Some(DisplayScope::Group(group)) => {
+                renderer.pop_layer(&LayerEffects {
+                    filters: &group.filters,
+                    filter_scale: self.pixel_scale(),
+                    color_transform: group.color_transform,
+                    blend_mode: group.blend_mode,
+                });
+            }
+            None => renderer.pop_layer(&LayerEffects::default()),
+        }
+
+        Ok(())
+    }
+
+    /// Render a primitive of a display list.
+    fn render_item<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        item: &DisplayItem,
+    ) -> Result<(), DomSymbolRenderError> {
+        let map_point = |x: f64, y: f64| {
+            let (x, y) = item.map_point(x, y);
+            let point = self.view_transform.transform_point(euclid::point2(x, y));
+            (point.x, point.y)
+        };
+        match &item.primitive {
+            Primitive::Shape(shape) => self.render_shape(
+                renderer,
+                shape,
+                &map_point,
+                &item.color_transform,
+                item.get_scale() * self.pixel_scale(),
+            ),
+            Primitive::Bitmap { bitmap_item, .. } => {
+                self.render_bitmap(renderer, bitmap_item, &map_point, &item.color_transform)
+            }
+            Primitive::Text(text) => {
+                self.render_text(renderer, text, &map_point, &item.color_transform)
+            }
+        }
+    }
+
+    fn render_bitmap<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        bitmap_item: &DomBitmapItem,
+        map_point: &dyn Fn(f64, f64) -> (f64, f64),
+        color_transform: &ColorTransform,
+    ) -> Result<(), DomSymbolRenderError> {
+        let bitmap = self.get_bitmap(&bitmap_item.name)?;
+
+        // Bitmaps can only be drawn with an affine transform,
+        // so projected bitmaps use the one matching three of their corners.
+        // Bitmaps are measured in pixels, but points are mapped in twips.
+        let width = f64::from(bitmap.width.max(1));
+        let height = f64::from(bitmap.height.max(1));
+        let (x0, y0) = map_point(0.0, 0.0);
+        let (x1, y1) = map_point(width * TWIPS_PER_PIXEL, 0.0);
+        let (x2, y2) = map_point(0.0, height * TWIPS_PER_PIXEL);
+        let transform = euclid::default::Transform2D::new(
+            (x1 - x0) / width,
+            (y1 - y0) / width,
+            (x2 - x0) / height,
+            (y2 - y0) / height,
+            x0,
+            y0,
+        );
+        if transform.inverse().is_none() {
+            return Ok(());
+        }
+
+        let bitmap: Cow<Bitmap> = if color_transform.is_identity() {
+            Cow::Borrowed(&bitmap)
+        } else {
+            Cow::Owned(Bitmap {
+                width: bitmap.width,
+                height: bitmap.height,
+                data: bitmap
+                    .data
+                    .iter()
+                    .map(|pixel| color_transform.apply_premultiplied_argb(*pixel))
+                    .collect(),
+            })
+        };
+        renderer.draw_bitmap(
+            &bitmap,
+            &transform,
+            bitmap_item.allow_smoothing.unwrap_or(false),
+        );
+
+        Ok(())
+    }
+
+    fn render_text<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        text: &DomText,
+        map_point: &dyn Fn(f64, f64) -> (f64, f64),
+        color_transform: &ColorTransform,
+    ) -> Result<(), DomSymbolRenderError> {
+        for glyph_run in layout_text(self.fla, self.font_library, text)? {
+            let path: Vec<_> = glyph_run
+                .path
+                .iter()
+                .map(|cmd| match *cmd {
+                    PathCommand::MoveTo(x, y) => {
+                        let (x, y) = map_point(x, y);
+                        PathCommand::MoveTo(x, y)
+                    }
+                    PathCommand::LineTo(x, y) => {
+                        let (x, y) = map_point(x, y);
+                        PathCommand::LineTo(x, y)
+                    }
+                    PathCommand::QuadTo(cx, cy, x, y) => {
+                        let (cx, cy) = map_point(cx, cy);
+                        let (x, y) = map_point(x, y);
+                        PathCommand::QuadTo(cx, cy, x, y)
+                    }
+                    PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => {
+                        let (cx1, cy1) = map_point(cx1, cy1);
+                        let (cx2, cy2) = map_point(cx2, cy2);
+                        let (x, y) = map_point(x, y);
+                        PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y)
+                    }
+                    PathCommand::Close => PathCommand::Close,
+                })
+                .collect();
+
+            let alpha = (glyph_run.alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+            renderer.fill_path(
+                &path,
+                Color::transformed(color_transform, glyph_run.color, alpha),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get a decoded bitmap, decoding it if this is the first time it is used.
+    fn get_bitmap(&self, name: &str) -> Result<Rc<Bitmap>, DomSymbolRenderError> {
+        if let Some(bitmap) = self.bitmaps.borrow().get(name) {
+            return Ok(bitmap.clone());
+        }
+
+        let bitmap = self
+            .fla
+            .decode_bitmap(name)
+            .ok_or_else(|| DomSymbolRenderError::MissingBitmapData(name.to_string()))??;
+        let bitmap = Rc::new(bitmap);
+        self.bitmaps
+            .borrow_mut()
+            .insert(name.to_string(), bitmap.clone());
+
+        Ok(bitmap)
+    }
+
+    /// The number of pixels of the render target in a pixel of the rendered symbol.
+    fn pixel_scale(&self) -> f64 {
+        TWIPS_PER_PIXEL * self.scale
+    }
+
+    /// Render a shape, with `stroke_scale` pixels of the render target in a pixel of the shape.
+    fn render_shape<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        shape: &DomShape,
+        map_point: &dyn Fn(f64, f64) -> (f64, f64),
+        color_transform: &ColorTransform,
+        stroke_scale: f64,
+    ) -> Result<(), DomSymbolRenderError> {
+        for edge in shape.get_edges().iter() {
+            let mut path = Vec::new();
+            let mut last_selection_mask = None;
+
+            for cmd in edge
+                .get_edge_definition_commands()
+                .iter()
+                .flat_map(|cmds| cmds.iter())
+            {
+                match cmd {
+                    EdgeDefinitionCommand::MoveTo(x, y) => {
+                        let (x, y) = map_point(*x, *y);
+
+                        if last_selection_mask.is_none() {
+                            path.push(PathCommand::MoveTo(x, y));
+                        } else {
+                            path.push(PathCommand::LineTo(x, y));
+                        }
+                    }
+                    EdgeDefinitionCommand::LineTo(x, y) => {
+                        let (x, y) = map_point(*x, *y);
+                        path.push(PathCommand::LineTo(x, y));
+                    }
+                    EdgeDefinitionCommand::Selection(selection_mask) => {
+                        if let Some(_last_selection_mask) = last_selection_mask {
+                            // TODO: Draw the current path and start a new one.
+                            return Err(DomSymbolRenderError::Unsupported(
+                                "SelectionMask overwrite",
+                            ));
+                        } else {
+                            last_selection_mask = Some(selection_mask);
+                        }
+                    }
+                    EdgeDefinitionCommand::CurveTo(x, y, ex, ey) => {
+                        let (x, y) = map_point(*x, *y);
+                        let (ex, ey) = map_point(*ex, *ey);
+                        path.push(PathCommand::QuadTo(x, y, ex, ey));
+                    }
+                }
+            }
+            path.push(PathCommand::Close);
+
+            if let Some(selection_mask) = last_selection_mask {
+                if selection_mask.contains(SelectionMask::FILLSTYLE0) {
+                    return Err(DomSymbolRenderError::Unsupported("FILLSTYLE0"));
+                }
+
+                if selection_mask.contains(SelectionMask::FILLSTYLE1) {
+                    let fill_style_1_index = edge
+                        .fill_style_1
+                        .ok_or(DomSymbolRenderError::MissingFillStyleIndex(1))?;
+                    let fill_style_1 = shape
+                        .get_fill_style(fill_style_1_index)
+                        .ok_or(DomSymbolRenderError::MissingFillStyle(fill_style_1_index))?;
+
+                    // Only support solid color for now
+                    let color = fill_style_1
+                        .solid_color
+                        .as_ref()
+                        .ok_or(DomSymbolRenderError::MissingColor)?
+                        .get_rgb();
+
+                    renderer.fill_path(&path, Color::transformed(color_transform, color, 0xFF));
+                }
+
+                if selection_mask.contains(SelectionMask::STROKE) {
+                    let stroke_style_index = edge
+                        .stroke_style
+                        .ok_or(DomSymbolRenderError::MissingStrokeStyleIndex)?;
+                    let stroke_style = shape
+                        .get_stroke_style(stroke_style_index)
+                        .ok_or(DomSymbolRenderError::MissingStrokeStyle(stroke_style_index))?;
+
+                    // Only support solid color for now
+                    let color = stroke_style
+                        .solid_stroke
+                        .fill
+                        .solid_color
+                        .as_ref()
+                        .map(|solid_color| solid_color.get_rgb())
+                        .unwrap_or((0, 0, 0));
+
+                    renderer.stroke_path(
+                        &path,
+                        Color::transformed(color_transform, color, 0xFF),
+                        stroke_style.solid_stroke.get_weight() * stroke_scale,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        dot,
+        frame,
+        instance,
+        layer,
+        make_fla,
+        square,
+        symbol,
+    };
+
+    /// A call to a backend.
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Fill(Color),
+        Stroke(Color, f64),
+        Bitmap,
+        Push,
+        Pop {
+            num_filters: usize,
+            color_transform: ColorTransform,
+            blend_mode: BlendMode,
+        },
+        PopMask,
+    }
+
+    /// A backend recording the calls made to it.
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<Call>,
+    }
+
+    impl Renderer for Recorder {
+        fn fill_path(&mut self, _path: &[PathCommand], color: Color) {
+            self.calls.push(Call::Fill(color));
+        }
+
+        fn stroke_path(&mut self, _path: &[PathCommand], color: Color, width: f64) {
+            self.calls.push(Call::Stroke(color, width));
+        }
+
+        fn draw_bitmap(
+            &mut self,
+            _bitmap: &Bitmap,
+            _transform: &euclid::default::Transform2D<f64>,
+            _smooth: bool,
+        ) {
+            self.calls.push(Call::Bitmap);
+        }
+
+        fn push_layer(&mut self) {
+            self.calls.push(Call::Push);
+        }
+
+        fn pop_layer(&mut self, effects: &LayerEffects) {
+            self.calls.push(Call::Pop {
+                num_filters: effects.filters.len(),
+                color_transform: effects.color_transform,
+                blend_mode: effects.blend_mode,
+            });
+        }
+
+        fn pop_mask(&mut self) {
+            self.calls.push(Call::PopMask);
+        }
+    }
+
+    /// Record the calls made to render the first frame of a symbol.
+    fn record(fla: &Fla, name: &str) -> Vec<Call> {
+        let options = RenderOptions::default();
+        let view = View {
+            width: 100,
+            height: 100,
+            transform: euclid::default::Transform2D::identity(),
+            scale: 1.0,
+        };
+        let mut recorder = Recorder::default();
+        TimelineRenderer::new(fla, &view, &options)
+            .render_symbol(&mut recorder, fla.get_symbol(name).unwrap(), 0, None)
+            .unwrap();
+
+        recorder.calls
+    }
+
+    fn opaque(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 0xFF }
+    }
+
+    const POP: Call = Call::Pop {
+        num_filters: 0,
+        color_transform: ColorTransform::IDENTITY,
+        blend_mode: BlendMode::Normal,
+    };
+
+    /// Render the first frame of a symbol with a pixel of the target for each pixel of the symbol, without padding.
+    #[cfg(feature = "render-raqote")]
+    fn render(fla: &Fla, name: &str) -> ::raqote::DrawTarget {
+        fla.get_symbol(name)
+            .unwrap()
+            .render_raqote_frame(
+                fla,
+                &RenderOptions::default(),
+                1.0 / TWIPS_PER_PIXEL,
+                0.0,
+                0,
+            )
+            .unwrap()
+    }
+
+    /// Get a pixel of a render target as premultiplied `0xAARRGGBB`.
+    #[cfg(feature = "render-raqote")]
+    fn pixel(target: &::raqote::DrawTarget, x: i32, y: i32) -> u32 {
+        target.get_data()[(y * target.width() + x) as usize]
+    }
+
+    #[test]
+    fn stroke_width() {
+        let line = r##"<DOMShape><strokes><StrokeStyle index="1"><SolidStroke weight="2"><fill><SolidColor color="#000000"/></fill></SolidStroke></StrokeStyle></strokes><edges><Edge strokeStyle="1" edges="!0 0S4|200 0"/></edges></DOMShape>"##;
+        let fla = make_fla(
+            &[
+                ("Line", symbol("Line", "", &layer("", &frame(0, "", line)))),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &frame(
+                                0,
+                                "",
+                                &instance("Line", "", r#"<matrix><Matrix a="3" d="3"/></matrix>"#),
+                            ),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The weight is scaled by the instance, then by the view, which draws a twip to a pixel.
+        assert_eq!(
+            record(&fla, "Main"),
+            [Call::Stroke(
+                opaque(0x00, 0x00, 0x00),
+                2.0 * 3.0 * TWIPS_PER_PIXEL
+            )]
+        );
+    }
+
+    #[test]
+    fn masked_layer_calls() {
+        let layers = layer(r#"layerType="mask""#, &frame(0, "", &square("#0000FF", 10)))
+            + &layer(
+                r#"parentLayerIndex="0""#,
+                &frame(0, "", &square("#FF0000", 20)),
+            )
+            + &layer("", &frame(0, "", &square("#00FF00", 30)));
+        let fla = make_fla(&[("Main", symbol("Main", "", &layers))], "", &[]);
+
+        // The masked layer is drawn in its own layer, which the mask layer then clips.
+        assert_eq!(
+            record(&fla, "Main"),
+            [
+                Call::Fill(opaque(0x00, 0xFF, 0x00)),
+                Call::Push,
+                Call::Fill(opaque(0xFF, 0x00, 0x00)),
+                Call::Push,
+                Call::Fill(opaque(0x00, 0x00, 0xFF)),
+                Call::PopMask,
+                POP,
+            ]
+        );
+    }
+
+    #[test]
+    fn filtered_instance_calls() {
+        let instance = instance(
+            "Dot",
+            "",
+            r##"<color><Color alphaMultiplier="0.5"/></color><filters><GlowFilter color="#FF0000"/></filters>"##,
+        );
+        let fla = make_fla(
+            &[
+                dot(),
+                (
+                    "Main",
+                    symbol("Main", "", &layer("", &frame(0, "", &instance))),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The color transform applies to the filtered layer, not to the shapes drawn into it.
+        let mut half = ColorTransform::IDENTITY;
+        half.multipliers[3] = 0.5;
+        assert_eq!(
+            record(&fla, "Main"),
+            [
+                Call::Push,
+                Call::Fill(opaque(0x00, 0x00, 0xFF)),
+                Call::Pop {
+                    num_filters: 1,
+                    color_transform: half,
+                    blend_mode: BlendMode::Normal,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fit_filtered_symbol() {
+        let instance = instance(
+            "Dot",
+            "",
+            r#"<filters><BlurFilter blurX="10" blurY="4" quality="1"/></filters>"#,
+        );
+        let fla = make_fla(
+            &[
+                dot(),
+                (
+                    "Main",
+                    symbol("Main", "", &layer("", &frame(0, "", &instance))),
+                ),
+            ],
+            "",
+            &[],
+        );
+        let symbol = fla.get_symbol("Main").unwrap();
+        let layer_policy = LayerPolicy::default();
+
+        // The blur spreads 5 pixels left and right and 2 pixels up and down, past the 10 pixel dot.
+        let view =
+            View::fit_symbol(symbol, &fla, &layer_policy, 1.0 / TWIPS_PER_PIXEL, 0.0).unwrap();
+        assert_eq!((view.width, view.height), (20, 14));
+        assert_eq!(
+            view.transform.transform_point(euclid::point2(
+                -5.0 * TWIPS_PER_PIXEL,
+                -2.0 * TWIPS_PER_PIXEL
+            )),
+            euclid::point2(0.0, 0.0)
+        );
+
+        // The bounding box of the symbol itself does not include the blur.
+        let bounding_box = symbol.calc_bounding_box(&fla, &layer_policy).unwrap();
+        assert_eq!(bounding_box.width(), 10.0 * TWIPS_PER_PIXEL);
+    }
+
+    #[test]
+    #[cfg(feature = "render-raqote")]
+    fn masked_layer() {
+        let mask = instance(
+            "Dot",
+            "",
+            r#"<color><Color alphaMultiplier="0.25"/></color>"#,
+        );
+        let layers = layer(r#"layerType="mask""#, &frame(0, "", &mask))
+            + &layer(
+                r#"parentLayerIndex="0""#,
+                &frame(0, "", &square("#FF0000", 20)),
+            )
+            + &layer("", &frame(0, "", &square("#00FF00", 20)));
+        let fla = make_fla(&[dot(), ("Main", symbol("Main", "", &layers))], "", &[]);
+
+        // The mask clips by its shape, so its alpha does not fade the masked layer.
+        let target = render(&fla, "Main");
+        assert_eq!(pixel(&target, 5, 5), 0xFFFF0000);
+        assert_eq!(pixel(&target, 15, 15), 0xFF00FF00);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    bitmap::Bitmap,
+    render::{
+        Color,
+        LayerEffects,
+        Renderer,
+    },
+    text::PathCommand,
+    types::dom_symbol_instance::BlendMode,
+};
+
+/// A backend drawing with raqote.
+pub struct RaqoteRenderer {
+    /// The render target, followed by the layers pushed onto it.
+    layers: Vec<raqote::DrawTarget>,
+    draw_options: raqote::DrawOptions,
+}
+
+impl RaqoteRenderer {
+    /// Make a backend drawing to a new, transparent target.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            layers: vec![raqote::DrawTarget::new(width, height)],
+            draw_options: raqote::DrawOptions::new(),
+        }
+    }
+
+    /// Get the render target, drawing any layers that were not popped.
+    pub fn finish(mut self) -> raqote::DrawTarget {
+        while self.layers.len() > 1 {
+            self.pop_layer(&LayerEffects::default());
+        }
+
+        self.layers.remove(0)
+    }
+
+    fn target(&mut self) -> &mut raqote::DrawTarget {
+        self.layers
+            .last_mut()
+            .expect("the render target is never popped")
+    }
+
+    /// Pop the top layer, leaving the render target in place.
+    fn pop(&mut self) -> Option<raqote::DrawTarget> {
+        if self.layers.len() > 1 {
+            self.layers.pop()
+        } else {
+            None
+        }
+    }
+}
+
+impl Renderer for RaqoteRenderer {
+    fn fill_path(&mut self, path: &[PathCommand], color: Color) {
+        let path = build_path(path);
+        let source = raqote::Source::Solid(solid_source(color));
+        let draw_options = self.draw_options;
+        self.target().fill(&path, &source, &draw_options);
+    }
+
+    fn stroke_path(&mut self, path: &[PathCommand], color: Color, width: f64) {
+        let path = build_path(path);
+        let source = raqote::Source::Solid(solid_source(color));
+        let stroke_style = raqote::StrokeStyle {
+            cap: raqote::LineCap::Round,
+            join: raqote::LineJoin::Round,
+            width: width as f32,
+            ..Default::default()
+        };
+        let draw_options = self.draw_options;
+        self.target()
+            .stroke(&path, &source, &stroke_style, &draw_options);
+    }
+
+    fn draw_bitmap(
+        &mut self,
+        bitmap: &Bitmap,
+        transform: &euclid::default::Transform2D<f64>,
+        smooth: bool,
+    ) {
+        let inverse_transform = match transform.inverse() {
+            Some(inverse_transform) => inverse_transform,
+            None => return,
+        };
+
+        let mut pb = raqote::PathBuilder::new();
+        pb.rect(0.0, 0.0, bitmap.width as f32, bitmap.height as f32);
+        let path = pb.finish().transform(&transform.cast());
+
+        let filter_mode = if smooth {
+            raqote::FilterMode::Bilinear
+        } else {
+            raqote::FilterMode::Nearest
+        };
+        let image = raqote::Image {
+            width: bitmap.width as i32,
+            height: bitmap.height as i32,
+            data: &bitmap.data,
+        };
+        let source = raqote::Source::Image(
+            image,
+            raqote::ExtendMode::Pad,
+            filter_mode,
+            inverse_transform.cast(),
+        );
+        let draw_options = self.draw_options;
+        self.target().fill(&path, &source, &draw_options);
+    }
+
+    fn push_layer(&mut self) {
+        let target = self.target();
+        let layer = raqote::DrawTarget::new(target.width(), target.height());
+        self.layers.push(layer);
+    }
+
+    fn pop_layer(&mut self, effects: &LayerEffects) {
+        let layer = match self.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+
+        let mut bitmap = Bitmap {
+            width: layer.width() as u32,
+            height: layer.height() as u32,
+            data: layer.into_vec(),
+        };
+        for filter in effects.filters.iter() {
+            filter.apply(&mut bitmap, effects.filter_scale);
+        }
+        if !effects.color_transform.is_identity() {
+            for pixel in bitmap.data.iter_mut() {
+                *pixel = effects.color_transform.apply_premultiplied_argb(*pixel);
+            }
+        }
+
+        let draw_options = self.draw_options;
+        let target = self.target();
+        if effects.blend_mode == BlendMode::Normal {
+            let image = raqote::Image {
+                width: bitmap.width as i32,
+                height: bitmap.height as i32,
+                data: &bitmap.data,
+            };
+            target.draw_image_at(0.0, 0.0, &image, &draw_options);
+        } else {
+            for (dst, src) in target.get_data_mut().iter_mut().zip(bitmap.data.iter()) {
+                *dst = effects.blend_mode.blend(*src, *dst);
+            }
+        }
+    }
+
+    fn pop_mask(&mut self) {
+        let mask = match self.pop() {
+            Some(mask) => mask,
+            None => return,
+        };
+
+        apply_mask(self.target().get_data_mut(), mask.get_data());
+    }
+}
+
+fn build_path(path: &[PathCommand]) -> raqote::Path {
+    let mut pb = raqote::PathBuilder::new();
+    for cmd in path.iter() {
+        match *cmd {
+            PathCommand::MoveTo(x, y) => pb.move_to(x as f32, y as f32),
+            PathCommand::LineTo(x, y) => pb.line_to(x as f32, y as f32),
+            PathCommand::QuadTo(cx, cy, x, y) => {
+                pb.quad_to(cx as f32, cy as f32, x as f32, y as f32)
+            }
+            PathCommand::CubicTo(cx1, cy1, cx2, cy2, x, y) => pb.cubic_to(
+                cx1 as f32, cy1 as f32, cx2 as f32, cy2 as f32, x as f32, y as f32,
+            ),
+            PathCommand::Close => pb.close(),
+        }
+    }
+
+    pb.finish()
+}
+
+fn solid_source(color: Color) -> raqote::SolidSource {
+    raqote::SolidSource::from_unpremultiplied_argb(color.a, color.r, color.g, color.b)
+}
+
+/// Clip premultiplied ARGB pixels to the alpha of a rendered mask.
+fn apply_mask(pixels: &mut [u32], mask: &[u32]) {
+    for (pixel, mask) in pixels.iter_mut().zip(mask.iter()) {
+        let coverage = mask >> 24;
+        if coverage == 0xFF {
+            continue;
+        }
+
+        let [a, r, g, b] = pixel.to_be_bytes();
+        let scale = |channel: u8| ((u32::from(channel) * coverage + 127) / 255) as u8;
+        *pixel = u32::from_be_bytes([scale(a), scale(r), scale(g), scale(b)]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::ColorTransform;
+
+    /// A square path from `min` to `max` pixels.
+    fn square(min: f64, max: f64) -> Vec<PathCommand> {
+        vec![
+            PathCommand::MoveTo(min, min),
+            PathCommand::LineTo(max, min),
+            PathCommand::LineTo(max, max),
+            PathCommand::LineTo(min, max),
+            PathCommand::Close,
+        ]
+    }
+
+    fn color(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    #[test]
+    fn mask_scales_by_alpha() {
+        let mut pixels = [0xFFFF0000, 0xFFFF0000, 0xFFFF0000, 0x80400000];
+        apply_mask(
+            &mut pixels,
+            &[0xFF00FF00, 0x80000000, 0x00000000, 0x80008000],
+        );
+        assert_eq!(pixels, [0xFFFF0000, 0x80800000, 0x00000000, 0x40200000]);
+    }
+
+    #[test]
+    fn pop_mask_clips_the_layer_below() {
+        let mut renderer = RaqoteRenderer::new(4, 4);
+        renderer.push_layer();
+        renderer.fill_path(&square(0.0, 4.0), color(0xFF, 0x00, 0x00, 0xFF));
+        renderer.push_layer();
+        renderer.fill_path(&square(0.0, 2.0), color(0x00, 0x00, 0xFF, 0xFF));
+        renderer.pop_mask();
+        renderer.pop_layer(&LayerEffects::default());
+
+        let target = renderer.finish();
+        assert_eq!(target.get_data()[0], 0xFFFF0000);
+        assert_eq!(target.get_data()[3 * 4 + 3], 0x00000000);
+    }
+
+    #[test]
+    fn pop_layer_blends() {
+        let mut renderer = RaqoteRenderer::new(2, 1);
+        renderer.fill_path(&square(0.0, 2.0), color(0x80, 0xFF, 0xFF, 0xFF));
+        renderer.push_layer();
+        renderer.fill_path(&square(0.0, 1.0), color(0xFF, 0x80, 0x00, 0xFF));
+        renderer.pop_layer(&LayerEffects {
+            blend_mode: BlendMode::Multiply,
+            ..LayerEffects::default()
+        });
+
+        // Only the pixel the layer covers is multiplied.
+        let target = renderer.finish();
+        assert_eq!(target.get_data(), [0xFF808000, 0xFF80FFFF]);
+    }
+
+    #[test]
+    fn pop_layer_applies_color_transform() {
+        let mut renderer = RaqoteRenderer::new(1, 1);
+        renderer.push_layer();
+        renderer.fill_path(&square(0.0, 1.0), color(0xFF, 0x00, 0x00, 0xFF));
+        let mut half = ColorTransform::IDENTITY;
+        half.multipliers[3] = 0.5;
+        renderer.pop_layer(&LayerEffects {
+            color_transform: half,
+            ..LayerEffects::default()
+        });
+
+        assert_eq!(renderer.finish().get_data(), [0x80800000]);
+    }
+}
@@ -0,0 +1,599 @@
+use crate::{
+    tween::{
+        MotionObjectTween,
+        MotionTween,
+        ShapeTween,
+    },
+    types::{
+        dom_frame::Element,
+        dom_shape::path::GuidePath,
+        dom_symbol::ButtonState,
+        dom_symbol_instance::SymbolType,
+        dom_timeline::LayerPolicy,
+        ColorTransform,
+        DomBitmapInstance,
+        DomBitmapItem,
+        DomShape,
+        DomSymbol,
+        DomSymbolInstance,
+        DomText,
+        DomTimeline,
+        Filter,
+        Matrix,
+        Point,
+    },
+    Fla,
+};
+use std::borrow::Cow;
+
+/// The deepest that symbols may be nested in each other.
+///
+/// Symbols containing themselves, directly or through other symbols, would otherwise nest forever.
+pub const MAX_SYMBOL_DEPTH: usize = 64;
+
+/// An error that occured while sampling a timeline
+#[derive(Debug, thiserror::Error)]
+pub enum SampleError {
+    #[error("Missing Symbol '{0}'")]
+    MissingSymbol(String),
+
+    #[error("Missing Bitmap '{0}'")]
+    MissingBitmap(String),
+
+    #[error("Symbol '{0}' is nested more than {} symbols deep", MAX_SYMBOL_DEPTH)]
+    RecursionLimit(String),
+}
+
+/// Check that a symbol nested `depth` symbols deep in the root symbol does not exceed [`MAX_SYMBOL_DEPTH`].
+pub(crate) fn check_symbol_depth(symbol: &DomSymbol, depth: usize) -> Result<(), SampleError> {
+    if depth > MAX_SYMBOL_DEPTH {
+        return Err(SampleError::RecursionLimit(symbol.name.clone()));
+    }
+
+    Ok(())
+}
+
+/// A sampler that resolves what each timeline shows at a given frame.
+///
+/// Sampling is stateless, so movie clips are approximated as playing from their keyframe,
+/// looping along with the timeline holding them. This matches a [`Player`](crate::player::Player)
+/// until that timeline stops, loops back or reaches another keyframe holding the clip,
+/// where the player keeps the playhead of the clip running. Use a player to follow movie clips exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct TimelineSampler<'a> {
+    fla: &'a Fla,
+    layer_policy: LayerPolicy,
+}
+
+impl<'a> TimelineSampler<'a> {
+    /// Make a new sampler, resolving symbols from the given fla and skipping layers according to a layer policy.
+    pub fn new(fla: &'a Fla, layer_policy: LayerPolicy) -> Self {
+        Self { fla, layer_policy }
+    }
+
+    /// Get the policy deciding which layers are sampled.
+    pub fn get_layer_policy(&self) -> &LayerPolicy {
+        &self.layer_policy
+    }
+
+    /// Sample the timeline of a symbol at a frame, 0-indexed.
+    pub fn sample_symbol(
+        &self,
+        symbol: &'a DomSymbol,
+        frame_index: usize,
+    ) -> Result<Vec<SampledElement<'a>>, SampleError> {
+        self.sample_timeline(&symbol.timeline.dom_timeline, frame_index)
+    }
+
+    /// Sample a timeline at a frame, 0-indexed.
+    ///
+    /// Elements are returned in the order they should be drawn.
+    /// The first layer is the top layer, so layers are drawn from last to first.
+    /// Layers are skipped according to the layer policy of the sampler.
+    pub fn sample_timeline(
+        &self,
+        timeline: &'a DomTimeline,
+        frame_index: usize,
+    ) -> Result<Vec<SampledElement<'a>>, SampleError> {
+        let mut ret = Vec::new();
+        for (layer_index, layer) in timeline.get_layers().iter().enumerate().rev() {
+            if !timeline.should_render_layer(layer_index, &self.layer_policy) {
+                continue;
+            }
+            let frame = match layer.frame_at(frame_index) {
+                Some(frame) => frame,
+                None => continue,
+            };
+            let elapsed = frame_index - frame.index as usize;
+
+            // Tweens interpolate towards the next keyframe, if it starts right after this one.
+            let next_frame = layer
+                .frame_at(frame.get_end())
+                .filter(|next_frame| next_frame.index as usize == frame.get_end());
+            let tween =
+                MotionTween::from_frame(frame)
+                    .zip(next_frame)
+                    .map(|(tween, next_frame)| {
+                        let progress = tween.get_progress(elapsed, frame.get_duration());
+                        (tween, next_frame, progress)
+                    });
+            let shape_tween = ShapeTween::from_frame(frame).zip(next_frame);
+
+            // Classic tweens of guided layers follow the strokes of the guide at the first keyframe.
+            let guide_path = tween.as_ref().and_then(|_| {
+                let guide = timeline.get_motion_guide(layer)?;
+                GuidePath::from_shapes(guide.frame_at(frame.index as usize)?.get_shapes())
+            });
+            let motion_object = MotionObjectTween::from_frame(frame).map(|tween| {
+                let frame_rate = self.fla.dom_document.frame_rate as f64;
+                let time = tween.get_time(elapsed, frame.get_duration(), frame_rate);
+                (tween, time)
+            });
+
+            for (element_index, element) in frame.get_elements().iter().enumerate() {
+                // Morphed shapes have their matrices applied to their edges.
+                let mut morphed = false;
+                let kind = match element {
+                    Element::Shape(shape) => {
+                        let end = shape_tween.as_ref().and_then(|(tween, next_frame)| {
+                            let end = next_frame.get_elements().get(element_index)?.as_shape()?;
+                            Some((tween, end))
+                        });
+                        match end {
+                            Some((tween, end)) => {
+                                let t = tween.get_progress(elapsed, frame.get_duration());
+                                morphed = true;
+                                SampledElementKind::Shape(Cow::Owned(tween.morph(shape, end, t)))
+                            }
+                            None => SampledElementKind::Shape(Cow::Borrowed(shape)),
+                        }
+                    }
+                    Element::SymbolInstance(symbol_instance) => {
+                        let symbol = self
+                            .fla
+                            .get_symbol(&symbol_instance.library_item_name)
+                            .ok_or_else(|| {
+                                SampleError::MissingSymbol(
+                                    symbol_instance.library_item_name.clone(),
+                                )
+                            })?;
+
+                        SampledElementKind::Symbol {
+                            symbol_instance,
+                            symbol,
+                            frame_index: get_nested_frame(symbol_instance, symbol, elapsed),
+                        }
+                    }
+                    Element::BitmapInstance(bitmap_instance) => {
+                        let bitmap_item = self
+                            .fla
+                            .get_bitmap_item(&bitmap_instance.library_item_name)
+                            .ok_or_else(|| {
+                                SampleError::MissingBitmap(
+                                    bitmap_instance.library_item_name.clone(),
+                                )
+                            })?;
+
+                        SampledElementKind::Bitmap {
+                            bitmap_instance,
+                            bitmap_item,
+                        }
+                    }
+                    Element::StaticText(text)
+                    | Element::DynamicText(text)
+                    | Element::InputText(text) => SampledElementKind::Text(text),
+                    Element::Unknown => continue,
+                };
+
+                let mut matrix = if morphed {
+                    Matrix::IDENTITY
+                } else {
+                    element_matrix(element)
+                };
+                let mut color_transform = element_color_transform(element);
+                let mut filters = Cow::Borrowed(element_filters(element));
+
+                let tween_end = tween.as_ref().and_then(|(tween, next_frame, progress)| {
+                    let end = next_frame.get_elements().get(element_index)?;
+                    Some((tween, end, progress)).filter(|_| can_tween(element, end))
+                });
+                if let Some((tween, end, progress)) = tween_end {
+                    let end_matrix = element_matrix(end);
+                    let transformation_point = element_transformation_point(element);
+                    matrix = match guide_path.as_ref() {
+                        Some(path) => tween.interpolate_matrix_along_path(
+                            &matrix,
+                            &end_matrix,
+                            &transformation_point,
+                            progress,
+                            path,
+                        ),
+                        None => tween.interpolate_matrix(
+                            &matrix,
+                            &end_matrix,
+                            &transformation_point,
+                            progress,
+                        ),
+                    };
+                    color_transform =
+                        color_transform.interpolate(&element_color_transform(end), progress.color);
+
+                    let end_filters = element_filters(end);
+                    if !filters.is_empty() || !end_filters.is_empty() {
+                        filters = Cow::Owned(interpolate_filters(
+                            &filters,
+                            end_filters,
+                            progress.filters,
+                        ));
+                    }
+                }
+
+                if let Some((tween, time)) = motion_object {
+                    matrix =
+                        tween.get_matrix(&matrix, &element_transformation_point(element), time);
+                    if let Some(motion_color_transform) = tween.get_color_transform(time) {
+                        color_transform = motion_color_transform;
+                    }
+                    if !filters.is_empty() {
+                        tween.apply_filters(filters.to_mut(), time);
+                    }
+                }
+
+                ret.push(SampledElement {
+                    layer_index,
+                    element_index,
+                    elapsed,
+                    matrix,
+                    color_transform,
+                    filters,
+                    kind,
+                });
+            }
+        }
+
+        Ok(ret)
+    }
+}
+
+/// An element of a timeline, resolved at a frame.
+#[derive(Debug)]
+pub struct SampledElement<'a> {
+    /// The index of the layer holding this element.
+    pub layer_index: usize,
+
+    /// The index of this element in the keyframe holding it.
+    pub element_index: usize,
+
+    /// The number of frames since the keyframe holding this element began.
+    pub elapsed: usize,
+
+    /// The matrix of this element.
+    pub matrix: Matrix,
+
+    /// The color transform of this element.
+    pub color_transform: ColorTransform,
+
+    /// The filters of this element, in the order they are applied.
+    pub filters: Cow<'a, [Filter]>,
+
+    pub kind: SampledElementKind<'a>,
+}
+
+#[derive(Debug)]
+pub enum SampledElementKind<'a> {
+    /// A shape, which is owned if it is morphed by a shape tween.
+    Shape(Cow<'a, DomShape>),
+    Symbol {
+        symbol_instance: &'a DomSymbolInstance,
+        symbol: &'a DomSymbol,
+
+        /// The frame of the symbol that is shown.
+        frame_index: usize,
+    },
+    Bitmap {
+        bitmap_instance: &'a DomBitmapInstance,
+        bitmap_item: &'a DomBitmapItem,
+    },
+    Text(&'a DomText),
+}
+
+/// Get the frame of a symbol shown by an instance, `elapsed` frames after it was placed.
+///
+/// Movie clips are approximated as looping from their first frame when they are placed, see [`TimelineSampler`].
+/// Buttons show their up state.
+fn get_nested_frame(
+    symbol_instance: &DomSymbolInstance,
+    symbol: &DomSymbol,
+    elapsed: usize,
+) -> usize {
+    let num_frames = symbol.num_frames();
+    match symbol_instance.get_symbol_type() {
+        SymbolType::Graphic => symbol_instance.get_graphic_frame(elapsed, num_frames),
+        SymbolType::MovieClip => elapsed % num_frames.max(1),
+        SymbolType::Button => ButtonState::Up.get_frame_index(),
+    }
+}
+
+fn element_matrix(element: &Element) -> Matrix {
+    match element {
+        Element::Shape(shape) => shape.get_matrix(),
+        Element::SymbolInstance(symbol_instance) => symbol_instance.get_matrix(),
+        Element::BitmapInstance(bitmap_instance) => bitmap_instance.get_matrix(),
+        Element::StaticText(text) | Element::DynamicText(text) | Element::InputText(text) => {
+            text.get_matrix()
+        }
+        Element::Unknown => Matrix::IDENTITY,
+    }
+}
+
+/// Get the transformation point of an element, in pixels. Only symbol instances store one.
+fn element_transformation_point(element: &Element) -> Point {
+    match element {
+        Element::SymbolInstance(symbol_instance) => symbol_instance.get_transformation_point(),
+        _ => Point::default(),
+    }
+}
+
+/// Get the filters of an element. Only symbol instances have filters.
+fn element_filters(element: &Element) -> &[Filter] {
+    match element {
+        Element::SymbolInstance(symbol_instance) => symbol_instance
+            .filters
+            .as_ref()
+            .map(|filters| filters.filters.as_slice())
+            .unwrap_or(&[]),
+        _ => &[],
+    }
+}
+
+/// Check if a classic tween can interpolate between two elements.
+///
+/// Only instances of the same item are tweened. Shapes are tweened by shape tweens instead.
+fn can_tween(start: &Element, end: &Element) -> bool {
+    match (start, end) {
+        (Element::SymbolInstance(start), Element::SymbolInstance(end)) => {
+            start.library_item_name == end.library_item_name
+        }
+        (Element::BitmapInstance(start), Element::BitmapInstance(end)) => {
+            start.library_item_name == end.library_item_name
+        }
+        (Element::StaticText(_), Element::StaticText(_))
+        | (Element::DynamicText(_), Element::DynamicText(_))
+        | (Element::InputText(_), Element::InputText(_)) => true,
+        _ => false,
+    }
+}
+
+/// Interpolate between the filters of two keyframes.
+///
+/// Filters are paired in order. Filters missing from one of the keyframes are kept as they are.
+fn interpolate_filters(start: &[Filter], end: &[Filter], t: f64) -> Vec<Filter> {
+    let mut ret: Vec<Filter> = start
+        .iter()
+        .zip(end.iter())
+        .map(|(start, end)| start.interpolate(end, t))
+        .collect();
+    if start.len() > end.len() {
+        ret.extend_from_slice(&start[end.len()..]);
+    } else {
+        ret.extend_from_slice(&end[start.len()..]);
+    }
+
+    ret
+}
+
+/// Get the color transform of an element. Only symbol instances have color effects.
+fn element_color_transform(element: &Element) -> ColorTransform {
+    match element {
+        Element::SymbolInstance(symbol_instance) => symbol_instance.get_color_transform(),
+        _ => ColorTransform::IDENTITY,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        frame,
+        layer,
+        make_fla,
+        square,
+        symbol,
+    };
+
+    /// A symbol with four keyframes.
+    fn walk() -> (&'static str, String) {
+        let frames: String = (0..4)
+            .map(|i| frame(i, "", &square("#FF0000", 10)))
+            .collect();
+        ("Walk", symbol("Walk", "", &layer("", &frames)))
+    }
+
+    fn instance(name: &str, attrs: &str, tx: f64, ty: f64) -> String {
+        format!(
+            r#"<DOMSymbolInstance libraryItemName="{name}" {attrs}><matrix><Matrix tx="{tx}" ty="{ty}"/></matrix></DOMSymbolInstance>"#
+        )
+    }
+
+    fn sample<'a>(fla: &'a Fla, name: &str, frame_index: usize) -> Vec<SampledElement<'a>> {
+        TimelineSampler::new(fla, LayerPolicy::default())
+            .sample_symbol(fla.get_symbol(name).unwrap(), frame_index)
+            .unwrap()
+    }
+
+    fn nested_frame(sampled: &SampledElement) -> usize {
+        match sampled.kind {
+            SampledElementKind::Symbol { frame_index, .. } => frame_index,
+            _ => panic!("not a symbol instance"),
+        }
+    }
+
+    #[test]
+    fn graphic_loop_modes() {
+        let elements = [
+            r#"symbolType="graphic" loop="loop" firstFrame="2""#,
+            r#"symbolType="graphic" loop="play once" firstFrame="1""#,
+            r#"symbolType="graphic" loop="single frame" firstFrame="3""#,
+            r#"symbolType="graphic" loop="loop reverse" firstFrame="1""#,
+            r#"symbolType="movie clip""#,
+            r#"symbolType="button""#,
+        ]
+        .iter()
+        .map(|attrs| instance("Walk", attrs, 0.0, 0.0))
+        .collect::<String>();
+        let fla = make_fla(
+            &[
+                walk(),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &(frame(0, "", "") + &frame(1, r#"duration="9""#, &elements)),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The instances are placed at the second frame, so they are sampled from the frames elapsed since then.
+        let frames: Vec<Vec<usize>> = (1..6)
+            .map(|frame_index| {
+                sample(&fla, "Main", frame_index)
+                    .iter()
+                    .map(nested_frame)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                [2, 1, 3, 1, 0, 0],
+                [3, 2, 3, 0, 1, 0],
+                [0, 3, 3, 3, 2, 0],
+                [1, 3, 3, 2, 3, 0],
+                [2, 3, 3, 1, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn classic_tween_pairs_elements_by_index() {
+        let start = instance("Walk", "", 0.0, 0.0)
+            + &instance("Walk", "", 0.0, 0.0)
+            + &instance("Walk", "", 10.0, 0.0);
+        let end = instance("Walk", "", 40.0, 0.0) + &instance("Walk", "", 0.0, 80.0);
+        let fla = make_fla(
+            &[
+                walk(),
+                (
+                    "Main",
+                    symbol(
+                        "Main",
+                        "",
+                        &layer(
+                            "",
+                            &(frame(0, r#"duration="4" tweenType="motion""#, &start)
+                                + &frame(4, "", &end)),
+                        ),
+                    ),
+                ),
+            ],
+            "",
+            &[],
+        );
+
+        // The third instance has no instance to tween to, so it stays where it is.
+        let positions: Vec<_> = sample(&fla, "Main", 2)
+            .iter()
+            .map(|sampled| (sampled.element_index, sampled.matrix.tx, sampled.matrix.ty))
+            .collect();
+        assert_eq!(positions, [(0, 20.0, 0.0), (1, 0.0, 40.0), (2, 10.0, 0.0)]);
+    }
+
+    #[test]
+    fn classic_tween_follows_guide() {
+        let guide = r##"<DOMShape><strokes><StrokeStyle index="1"><SolidStroke><fill><SolidColor color="#000000"/></fill></SolidStroke></StrokeStyle></strokes><edges><Edge strokeStyle="1" edges="!0 0|800 0|800 800"/></edges></DOMShape>"##;
+        let layers = layer(r#"layerType="guide""#, &frame(0, r#"duration="5""#, guide))
+            + &layer(
+                r#"parentLayerIndex="0""#,
+                &(frame(
+                    0,
+                    r#"duration="4" tweenType="motion""#,
+                    &instance("Walk", "", 0.0, 0.0),
+                ) + &frame(4, "", &instance("Walk", "", 40.0, 40.0))),
+            );
+        let fla = make_fla(&[walk(), ("Main", symbol("Main", "", &layers))], "", &[]);
+
+        // Halfway along the guide is its corner, not halfway between the keyframes.
+        let sampled = sample(&fla, "Main", 2);
+        assert_eq!(sampled.len(), 1);
+        assert!((sampled[0].matrix.tx - 40.0).abs() < 1e-6);
+        assert!(sampled[0].matrix.ty.abs() < 1e-6);
+    }
+
+    #[test]
+    fn shape_tween_morphs_with_identity_matrix() {
+        let start = r##"<DOMShape><matrix><Matrix tx="10"/></matrix><fills><FillStyle index="1"><SolidColor color="#FF0000"/></FillStyle></fills><edges><Edge fillStyle1="1" edges="!0 0|200 0|200 200|0 200|0 0"/></edges></DOMShape>"##;
+        let fla = make_fla(
+            &[(
+                "Morph",
+                symbol(
+                    "Morph",
+                    "",
+                    &layer(
+                        "",
+                        &(frame(
+                            0,
+                            r#"duration="4" tweenType="shape""#,
+                            &(start.to_string() + start),
+                        ) + &frame(4, "", &square("#0000FF", 20))),
+                    ),
+                ),
+            )],
+            "",
+            &[],
+        );
+
+        // The first shape is morphed with its matrix applied to its edges,
+        // the second shape has no shape to morph to, so it keeps its matrix.
+        let sampled = sample(&fla, "Morph", 2);
+        assert_eq!(sampled.len(), 2);
+        assert!(matches!(
+            sampled[0].kind,
+            SampledElementKind::Shape(Cow::Owned(_))
+        ));
+        assert_eq!(sampled[0].matrix, Matrix::IDENTITY);
+        assert!(matches!(
+            sampled[1].kind,
+            SampledElementKind::Shape(Cow::Borrowed(_))
+        ));
+        assert_eq!(sampled[1].matrix.tx, 10.0);
+    }
+
+    #[test]
+    fn motion_object_tween() {
+        let motion_object = r#"<motionObjectXML><AnimationCore TimeScale="24000" Version="1" duration="4000"><TimeMap strength="0" type="Quadratic"/><PropertyContainer id="headContainer"><PropertyContainer id="Basic_Motion"><Property enabled="1" id="Motion_X" ignoreTimeMap="0"><Keyframe anchor="0,0" next="0,0" previous="0,0" timevalue="0"/><Keyframe anchor="0,40" next="0,0" previous="0,0" timevalue="4000"/></Property></PropertyContainer><PropertyContainer id="Colors"><PropertyContainer id="Alpha_Color"><Property enabled="1" id="Alpha_Amount" ignoreTimeMap="0"><Keyframe anchor="0,100" timevalue="0"/><Keyframe anchor="0,0" timevalue="4000"/></Property></PropertyContainer></PropertyContainer></PropertyContainer></AnimationCore></motionObjectXML>"#;
+        let keyframe = frame(
+            0,
+            r#"duration="4" tweenType="motion object""#,
+            &instance("Walk", "", 5.0, 0.0),
+        )
+        .replace("<elements>", &format!("{motion_object}<elements>"));
+        let fla = make_fla(
+            &[walk(), ("Main", symbol("Main", "", &layer("", &keyframe)))],
+            "",
+            &[],
+        );
+
+        // The motion is relative to the instance, and replaces its color transform.
+        let sampled = sample(&fla, "Main", 2);
+        assert_eq!(sampled.len(), 1);
+        assert!((sampled[0].matrix.tx - 25.0).abs() < 1e-6);
+        assert!((sampled[0].color_transform.multipliers[3] - 0.5).abs() < 1e-6);
+    }
+}
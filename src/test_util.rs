@@ -0,0 +1,95 @@
+//! Helpers for building small flas in tests.
+
+use crate::{
+    text::Font,
+    Fla,
+};
+use std::io::{
+    Cursor,
+    Write,
+};
+
+/// Make a fla from library symbols, named like their `DOMSymbolItem`, and other files.
+pub fn make_fla(symbols: &[(&str, String)], media: &str, files: &[(&str, &[u8])]) -> Fla {
+    let includes: String = symbols
+        .iter()
+        .map(|(name, _)| format!(r#"<Include href="{name}.xml" itemID="1" lastModified="1"/>"#))
+        .collect();
+    let document = format!(
+        r##"<DOMDocument backgroundColor="#FFFFFF" width="550" height="400" frameRate="24" currentTimeline="1" xflVersion="2.97" creatorInfo="test" platform="Windows" versionInfo="test" majorVersion="17" minorVersion="0" buildNumber="0" rulerVisible="true" viewAngle3D="23.524879648379" nextSceneIdentifier="2" playOptionsPlayLoop="false" playOptionsPlayPages="false" playOptionsPlayFrameActions="false" filetypeGUID="X" fileGUID="Y">
+            <fonts/><media>{media}</media><symbols>{includes}</symbols>
+            <timelines/><scripts/><persistentData/><PrinterSettings/><publishHistory/><swcCache/>
+        </DOMDocument>"##
+    );
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    zip.start_file("DOMDocument.xml", options).unwrap();
+    zip.write_all(document.as_bytes()).unwrap();
+    for (name, xml) in symbols {
+        zip.start_file(format!("LIBRARY/{name}.xml"), options)
+            .unwrap();
+        zip.write_all(xml.as_bytes()).unwrap();
+    }
+    for (name, data) in files {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    let data = zip.finish().unwrap().into_inner();
+
+    Fla::new(Cursor::new(data)).unwrap()
+}
+
+/// Make the xml of a library symbol with the given layers, from top to bottom.
+pub fn symbol(name: &str, attrs: &str, layers: &str) -> String {
+    format!(
+        r#"<DOMSymbolItem name="{name}" itemID="1" lastModified="1" {attrs}><timeline><DOMTimeline name="{name}"><layers>{layers}</layers></DOMTimeline></timeline></DOMSymbolItem>"#
+    )
+}
+
+/// Make the xml of a layer with the given keyframes.
+pub fn layer(attrs: &str, frames: &str) -> String {
+    format!(
+        r##"<DOMLayer name="layer" color="#000000" {attrs}><frames>{frames}</frames></DOMLayer>"##
+    )
+}
+
+/// Make the xml of a keyframe starting at a frame, 0-indexed.
+pub fn frame(index: usize, attrs: &str, elements: &str) -> String {
+    format!(
+        r#"<DOMFrame index="{index}" keyMode="9728" {attrs}><elements>{elements}</elements></DOMFrame>"#
+    )
+}
+
+/// Make the xml of a square shape filled with a color, from the origin to `size` pixels.
+pub fn square(color: &str, size: u32) -> String {
+    let size = size * 20;
+    format!(
+        r#"<DOMShape><fills><FillStyle index="1"><SolidColor color="{color}"/></FillStyle></fills><edges><Edge fillStyle1="1" edges="!0 0S2|{size} 0|{size} {size}|0 {size}|0 0"/></edges></DOMShape>"#
+    )
+}
+
+/// Make a library symbol named `Dot`, holding a 10 pixel blue square.
+pub fn dot() -> (&'static str, String) {
+    (
+        "Dot",
+        symbol("Dot", "", &layer("", &frame(0, "", &square("#0000FF", 10)))),
+    )
+}
+
+/// Make the xml of a graphic instance of a symbol, with attributes and elements like its matrix or color.
+pub fn instance(name: &str, attrs: &str, elements: &str) -> String {
+    format!(
+        r#"<DOMSymbolInstance libraryItemName="{name}" symbolType="graphic" {attrs}>{elements}</DOMSymbolInstance>"#
+    )
+}
+
+/// Load a font named `Squares`, with 1000 units per em, an ascent of 800 units and a descent of 200 units.
+///
+/// Printable characters are 500 unit squares advancing 500 units, except `*`, which is a 200 unit square.
+/// Spaces advance 250 units.
+pub fn squares_font() -> Font {
+    Font::new(include_bytes!("test_data/squares.ttf").to_vec())
+        .unwrap()
+        .with_name("Squares")
+}
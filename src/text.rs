@@ -0,0 +1,539 @@
+use crate::{
+    types::{
+        dom_text::{
+            Alignment,
+            DomTextAttrs,
+            LineType,
+        },
+        matrix::TWIPS_PER_PIXEL,
+        DomText,
+    },
+    Fla,
+};
+use std::borrow::Cow;
+
+/// The space between the edges of a text box and its text, in pixels.
+const GUTTER: f64 = 2.0;
+
+/// The character shown in place of every character of a password field.
+const PASSWORD_CHAR: char = '*';
+
+/// An error that occured while loading or resolving a font
+#[derive(Debug, thiserror::Error)]
+pub enum FontError {
+    #[error("Invalid font: {0}")]
+    InvalidFont(ttf_parser::FaceParsingError),
+
+    #[error("Missing Font '{0}', and no fallback font is registered")]
+    MissingFont(String),
+}
+
+/// A TrueType or OpenType font supplied by the caller.
+#[derive(Debug, Clone)]
+pub struct Font {
+    data: Vec<u8>,
+    index: u32,
+    names: Vec<String>,
+}
+
+impl Font {
+    /// Load a font from the data of a font file.
+    ///
+    /// The font can be found by its PostScript name, its full name or its family name.
+    pub fn new(data: Vec<u8>) -> Result<Self, FontError> {
+        Self::from_collection(data, 0)
+    }
+
+    /// Load a font from the data of a font collection, by its index in the collection.
+    pub fn from_collection(data: Vec<u8>, index: u32) -> Result<Self, FontError> {
+        let face = ttf_parser::Face::from_slice(&data, index).map_err(FontError::InvalidFont)?;
+
+        let mut names = Vec::new();
+        for name in face.names() {
+            let is_font_name = matches!(
+                name.name_id(),
+                ttf_parser::name_id::POST_SCRIPT_NAME
+                    | ttf_parser::name_id::FULL_NAME
+                    | ttf_parser::name_id::FAMILY
+            );
+            if !is_font_name {
+                continue;
+            }
+
+            if let Some(name) = name.to_string() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        Ok(Self { data, index, names })
+    }
+
+    /// Add a name the font can be found by, like the name of a font item.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Get the names the font can be found by.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Check if this font can be found by a name. Names are compared ignoring ascii case.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names
+            .iter()
+            .any(|font_name| font_name.eq_ignore_ascii_case(name))
+    }
+
+    fn get_face(&self) -> ttf_parser::Face<'_> {
+        // The data was parsed when the font was loaded, so this cannot fail.
+        ttf_parser::Face::from_slice(&self.data, self.index).expect("invalid font data")
+    }
+}
+
+/// The fonts used to render text.
+///
+/// Fla files do not embed the fonts used by text fields, so they must be supplied by the caller.
+#[derive(Debug, Clone, Default)]
+pub struct FontLibrary {
+    fonts: Vec<Font>,
+    fallback: Option<Font>,
+}
+
+impl FontLibrary {
+    /// Make a new, empty font library.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a font to the library.
+    ///
+    /// If multiple fonts have the same name, the first one added is used.
+    pub fn register(&mut self, font: Font) {
+        self.fonts.push(font);
+    }
+
+    /// Set the font used for text whose font is missing.
+    pub fn set_fallback(&mut self, font: Font) {
+        self.fallback = Some(font);
+    }
+
+    pub fn get_fallback(&self) -> Option<&Font> {
+        self.fallback.as_ref()
+    }
+
+    /// Get a font by name, without using the fallback font.
+    pub fn get(&self, name: &str) -> Option<&Font> {
+        self.fonts.iter().find(|font| font.has_name(name))
+    }
+}
+
+/// A command of a path.
+///
+/// Glyph outlines are in twips, while paths drawn by a [`crate::render::Renderer`] are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    QuadTo(f64, f64, f64, f64),
+    CubicTo(f64, f64, f64, f64, f64, f64),
+    Close,
+}
+
+/// Glyph outlines sharing the same color, in the space of a text field.
+#[derive(Debug, Clone)]
+pub struct GlyphRun {
+    pub color: (u8, u8, u8),
+    pub alpha: f64,
+    pub path: Vec<PathCommand>,
+}
+
+/// A glyph placed on a line, before the line is aligned.
+struct PlacedGlyph {
+    run_index: usize,
+    glyph_id: ttf_parser::GlyphId,
+    is_space: bool,
+    x: f64,
+    advance: f64,
+}
+
+/// A line of text, in pixels.
+#[derive(Default)]
+struct Line {
+    glyphs: Vec<PlacedGlyph>,
+    ascent: f64,
+    descent: f64,
+    leading: f64,
+}
+
+impl Line {
+    fn width(&self) -> f64 {
+        self.glyphs
+            .iter()
+            .rev()
+            .find(|glyph| !glyph.is_space)
+            .map(|glyph| glyph.x + glyph.advance)
+            .unwrap_or(0.0)
+    }
+
+    /// Split off the glyphs after the last space, moving them to the start of a new line.
+    ///
+    /// Returns `None` if there is nowhere to break this line.
+    fn split_at_last_space(&mut self) -> Option<Vec<PlacedGlyph>> {
+        let position = self.glyphs.iter().rposition(|glyph| glyph.is_space)?;
+        if position + 1 == self.glyphs.len() {
+            return None;
+        }
+
+        let mut rest = self.glyphs.split_off(position + 1);
+        let start = rest[0].x;
+        for glyph in rest.iter_mut() {
+            glyph.x -= start;
+        }
+
+        Some(rest)
+    }
+}
+
+/// A text run and the font used to draw it.
+struct ResolvedRun<'a> {
+    text_attrs: Cow<'a, DomTextAttrs>,
+    face: ttf_parser::Face<'a>,
+
+    /// The size of a font unit, in pixels.
+    scale: f64,
+}
+
+impl ResolvedRun<'_> {
+    fn ascent(&self) -> f64 {
+        f64::from(self.face.ascender()) * self.scale
+    }
+
+    fn descent(&self) -> f64 {
+        -f64::from(self.face.descender()) * self.scale
+    }
+}
+
+/// Lay out the text of a text field, producing glyph outlines in twips.
+///
+/// Lines are broken at line breaks, and wrapped at spaces if the text field has a fixed width.
+/// Fonts are resolved with [`Fla::resolve_font`].
+pub fn layout_text(
+    fla: &Fla,
+    font_library: &FontLibrary,
+    text: &DomText,
+) -> Result<Vec<GlyphRun>, FontError> {
+    let mut runs = Vec::with_capacity(text.get_text_runs().len());
+    for text_run in text.get_text_runs() {
+        let text_attrs = text_run.get_text_attrs();
+        let face = fla
+            .resolve_font(font_library, text_attrs.get_face())?
+            .get_face();
+        let scale = text_attrs.get_size() / f64::from(face.units_per_em().unwrap_or(1000));
+        runs.push(ResolvedRun {
+            text_attrs,
+            face,
+            scale,
+        });
+    }
+
+    let left = text.left.unwrap_or(0.0) + GUTTER;
+    let max_width = text
+        .width
+        .filter(|_| should_wrap(text))
+        .map(|width| width - GUTTER * 2.0);
+    let is_password = text.line_type == Some(LineType::Password);
+
+    let mut lines = vec![Line::default()];
+    let mut last_glyph: Option<(usize, ttf_parser::GlyphId)> = None;
+    for (run_index, (run, text_run)) in runs.iter().zip(text.get_text_runs()).enumerate() {
+        for c in text_run.characters.chars() {
+            let line = lines.last_mut().unwrap();
+            line.ascent = line.ascent.max(run.ascent());
+            line.descent = line.descent.max(run.descent());
+            line.leading = line.leading.max(run.text_attrs.get_line_spacing());
+
+            if c == '\r' || c == '\n' {
+                lines.push(Line::default());
+                last_glyph = None;
+                continue;
+            }
+
+            let c = if is_password { PASSWORD_CHAR } else { c };
+            let glyph_id = run.face.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0));
+
+            let mut x = line
+                .glyphs
+                .last()
+                .map_or(0.0, |glyph| glyph.x + glyph.advance);
+            if let Some((last_run_index, last_glyph_id)) = last_glyph {
+                if last_run_index == run_index && run.text_attrs.auto_kern.unwrap_or(false) {
+                    x += get_kerning(&run.face, last_glyph_id, glyph_id) * run.scale;
+                }
+            }
+
+            let advance = f64::from(run.face.glyph_hor_advance(glyph_id).unwrap_or(0)) * run.scale
+                + run.text_attrs.get_letter_spacing();
+            line.glyphs.push(PlacedGlyph {
+                run_index,
+                glyph_id,
+                is_space: c.is_whitespace(),
+                x,
+                advance,
+            });
+            last_glyph = Some((run_index, glyph_id));
+
+            if let Some(max_width) = max_width {
+                if x + advance > max_width && !c.is_whitespace() {
+                    if let Some(rest) = line.split_at_last_space() {
+                        let mut next_line = Line {
+                            glyphs: rest,
+                            ..Line::default()
+                        };
+                        next_line.ascent = run.ascent();
+                        next_line.descent = run.descent();
+                        next_line.leading = run.text_attrs.get_line_spacing();
+                        lines.push(next_line);
+                    }
+                }
+            }
+        }
+    }
+
+    // Empty lines take the height of the last run.
+    if let Some(run) = runs.last() {
+        for line in lines.iter_mut().filter(|line| line.ascent == 0.0) {
+            line.ascent = run.ascent();
+            line.descent = run.descent();
+            line.leading = run.text_attrs.get_line_spacing();
+        }
+    }
+
+    let box_width = text.width.map(|width| width - GUTTER * 2.0);
+    let mut ret: Vec<GlyphRun> = Vec::new();
+    let mut y = GUTTER;
+    for line in lines.iter() {
+        let baseline = y + line.ascent;
+        y = baseline + line.descent + line.leading;
+
+        let first_run = match line.glyphs.first() {
+            Some(glyph) => &runs[glyph.run_index],
+            None => continue,
+        };
+        let offset = match (first_run.text_attrs.get_alignment(), box_width) {
+            (Alignment::Center, Some(box_width)) => (box_width - line.width()) / 2.0,
+            (Alignment::Right, Some(box_width)) => box_width - line.width(),
+            _ => 0.0,
+        };
+
+        for glyph in line.glyphs.iter() {
+            let run = &runs[glyph.run_index];
+            let mut builder = OutlineBuilder {
+                path: Vec::new(),
+                x: left + offset + glyph.x,
+                y: baseline,
+                scale: run.scale,
+            };
+            if run
+                .face
+                .outline_glyph(glyph.glyph_id, &mut builder)
+                .is_none()
+            {
+                continue;
+            }
+
+            let color = run.text_attrs.get_fill_color().unwrap_or((0, 0, 0));
+            let alpha = run.text_attrs.get_alpha();
+            match ret.last_mut() {
+                Some(glyph_run) if glyph_run.color == color && glyph_run.alpha == alpha => {
+                    glyph_run.path.append(&mut builder.path);
+                }
+                _ => ret.push(GlyphRun {
+                    color,
+                    alpha,
+                    path: builder.path,
+                }),
+            }
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Check if the lines of a text field wrap at its width.
+///
+/// Static text wraps unless it expands to fit its text. Dynamic and input text wrap if they are multiline.
+fn should_wrap(text: &DomText) -> bool {
+    match text.line_type {
+        Some(line_type) => line_type == LineType::Multiline,
+        None => !text.auto_expand.unwrap_or(false),
+    }
+}
+
+/// Get the kerning between two glyphs, in font units.
+fn get_kerning(
+    face: &ttf_parser::Face,
+    left: ttf_parser::GlyphId,
+    right: ttf_parser::GlyphId,
+) -> f64 {
+    face.kerning_subtables()
+        .filter(|subtable| subtable.is_horizontal() && !subtable.is_variable())
+        .find_map(|subtable| subtable.glyphs_kerning(left, right))
+        .map(f64::from)
+        .unwrap_or(0.0)
+}
+
+/// Collects the outline of a glyph, converting font units to twips.
+struct OutlineBuilder {
+    path: Vec<PathCommand>,
+
+    /// The origin of the glyph, in pixels.
+    x: f64,
+    y: f64,
+
+    /// The size of a font unit, in pixels.
+    scale: f64,
+}
+
+impl OutlineBuilder {
+    fn point(&self, x: f32, y: f32) -> (f64, f64) {
+        // Font units point up, while twips point down.
+        (
+            (self.x + f64::from(x) * self.scale) * TWIPS_PER_PIXEL,
+            (self.y - f64::from(y) * self.scale) * TWIPS_PER_PIXEL,
+        )
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.push(PathCommand::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.push(PathCommand::LineTo(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.path.push(PathCommand::QuadTo(x1, y1, x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.path.push(PathCommand::CubicTo(x1, y1, x2, y2, x, y));
+    }
+
+    fn close(&mut self) {
+        self.path.push(PathCommand::Close);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        test_util::{
+            make_fla,
+            squares_font,
+        },
+        xml,
+    };
+
+    /// Lay out a text field with the `Squares` font, returning the origin of each glyph in pixels.
+    fn layout_origins(xml: &str) -> Vec<(f64, f64)> {
+        let mut font_library = FontLibrary::new();
+        font_library.register(squares_font());
+        let text: DomText = xml::from_str(xml).unwrap();
+
+        // Each glyph starts at its origin.
+        layout_text(&make_fla(&[], "", &[]), &font_library, &text)
+            .unwrap()
+            .iter()
+            .flat_map(|glyph_run| glyph_run.path.iter())
+            .filter_map(|command| match command {
+                PathCommand::MoveTo(x, y) => Some((x / TWIPS_PER_PIXEL, y / TWIPS_PER_PIXEL)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn static_text(width: f64, characters: &str, attrs: &str) -> String {
+        format!(
+            r#"<DOMStaticText width="{width}" height="40"><textRuns><DOMTextRun><characters>{characters}</characters><textAttrs><DOMTextAttrs face="Squares" size="20" {attrs}/></textAttrs></DOMTextRun></textRuns></DOMStaticText>"#
+        )
+    }
+
+    #[test]
+    fn wrap_lines() {
+        // Squares are 10 pixels wide and spaces 5 pixels, so only two words fit in each line.
+        // Lines are 20 pixels high, and 2 pixels apart by default.
+        let origins = layout_origins(&static_text(54.0, "aa aa aa", ""));
+        assert_eq!(
+            origins,
+            [
+                (2.0, 18.0),
+                (12.0, 18.0),
+                (27.0, 18.0),
+                (37.0, 18.0),
+                (2.0, 40.0),
+                (12.0, 40.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn align_lines() {
+        let origins = layout_origins(&static_text(44.0, "aa", r#"alignment="center""#));
+        assert_eq!(origins, [(12.0, 18.0), (22.0, 18.0)]);
+
+        let origins = layout_origins(&static_text(44.0, "aa", r#"alignment="right""#));
+        assert_eq!(origins, [(22.0, 18.0), (32.0, 18.0)]);
+    }
+
+    #[test]
+    fn letter_spacing() {
+        let origins = layout_origins(&static_text(100.0, "aaa", r#"letterSpacing="2""#));
+        assert_eq!(origins, [(2.0, 18.0), (14.0, 18.0), (26.0, 18.0)]);
+    }
+
+    #[test]
+    fn password() {
+        let mut font_library = FontLibrary::new();
+        font_library.register(squares_font());
+        let fla = make_fla(&[], "", &[]);
+        let layout = |line_type: &str, characters: &str| {
+            let text: DomText = xml::from_str(&format!(
+                r#"<DOMInputText width="100" height="40" lineType="{line_type}"><textRuns><DOMTextRun><characters>{characters}</characters><textAttrs><DOMTextAttrs face="Squares" size="20"/></textAttrs></DOMTextRun></textRuns></DOMInputText>"#
+            ))
+            .unwrap();
+            layout_text(&fla, &font_library, &text).unwrap()[0]
+                .path
+                .clone()
+        };
+
+        assert_eq!(layout("password", "ab"), layout("single line", "**"));
+        assert_ne!(layout("password", "ab"), layout("single line", "ab"));
+    }
+
+    #[test]
+    fn missing_font() {
+        let fla = make_fla(&[], "", &[]);
+        let text: DomText = xml::from_str(&static_text(100.0, "a", "")).unwrap();
+
+        let result = layout_text(&fla, &FontLibrary::new(), &text);
+        assert!(matches!(result, Err(FontError::MissingFont(face)) if face == "Squares"));
+
+        let mut font_library = FontLibrary::new();
+        font_library.set_fallback(squares_font());
+        assert_eq!(layout_text(&fla, &font_library, &text).unwrap().len(), 1);
+    }
+}
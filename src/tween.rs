@@ -0,0 +1,503 @@
+use crate::types::{
+    dom_frame::MotionTweenRotate,
+    dom_shape::{
+        morph::morph_shape,
+        path::GuidePath,
+    },
+    ease::{
+        EaseCurve,
+        EaseTarget,
+    },
+    lerp,
+    motion_object::AnimationCore,
+    ColorTransform,
+    DomFrame,
+    DomShape,
+    Filter,
+    Matrix,
+    Point,
+};
+use std::f64::consts::{
+    PI,
+    TAU,
+};
+
+/// The settings of a classic motion tween, read from the keyframe starting it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionTween {
+    pub easing: Easing,
+
+    pub rotate: MotionTweenRotate,
+
+    /// The number of full turns added by a clockwise or counter-clockwise rotation.
+    pub rotate_times: u64,
+
+    /// Whether the scale is interpolated. If not, the scale of the first keyframe is kept.
+    pub scale: bool,
+
+    /// Whether the instance is rotated to follow its motion guide.
+    ///
+    /// Without a motion guide, instances move in a straight line, so this has no effect.
+    pub orient_to_path: bool,
+}
+
+impl MotionTween {
+    /// Get the classic motion tween starting at a keyframe, if there is one.
+    pub fn from_frame(frame: &DomFrame) -> Option<Self> {
+        if !frame.is_motion_tween() {
+            return None;
+        }
+
+        Some(Self {
+            easing: Easing::from_frame(frame),
+            rotate: frame.get_motion_tween_rotate(),
+            rotate_times: frame.motion_tween_rotate_times.unwrap_or(0),
+            scale: frame.motion_tween_scale.unwrap_or(true),
+            orient_to_path: frame.motion_tween_orient_to_path.unwrap_or(false),
+        })
+    }
+
+    /// Get the progress of each property, `elapsed` frames into a keyframe lasting `duration` frames.
+    pub fn get_progress(&self, elapsed: usize, duration: usize) -> TweenProgress {
+        let t = elapsed as f64 / duration.max(1) as f64;
+        let get = |target| self.easing.get_curve(target).evaluate(t);
+
+        TweenProgress {
+            position: get(EaseTarget::Position),
+            rotation: get(EaseTarget::Rotation),
+            scale: get(EaseTarget::Scale),
+            color: get(EaseTarget::Color),
+            filters: get(EaseTarget::Filters),
+        }
+    }
+
+    /// Interpolate between the matrix of an instance at the first keyframe and at the next keyframe.
+    ///
+    /// The transformation point, in pixels, moves in a straight line,
+    /// and the instance is scaled, skewed and rotated around it.
+    pub fn interpolate_matrix(
+        &self,
+        start: &Matrix,
+        end: &Matrix,
+        transformation_point: &Point,
+        progress: &TweenProgress,
+    ) -> Matrix {
+        let start_parts = MatrixParts::new(start);
+        let end_parts = MatrixParts::new(end);
+
+        let (a, b, c, d) = if self.rotate == MotionTweenRotate::None {
+            let t = progress.scale;
+            (
+                lerp(start.a, end.a, t),
+                lerp(start.b, end.b, t),
+                lerp(start.c, end.c, t),
+                lerp(start.d, end.d, t),
+            )
+        } else {
+            let rotation = end_parts.skew_y - start_parts.skew_y;
+            let rotation = match self.rotate {
+                MotionTweenRotate::Clockwise => {
+                    rotation.rem_euclid(TAU) + TAU * self.rotate_times as f64
+                }
+                MotionTweenRotate::CounterClockwise => {
+                    (rotation.rem_euclid(TAU) - TAU) - TAU * self.rotate_times as f64
+                }
+                _ => normalize_angle(rotation),
+            };
+            let skew = normalize_angle(
+                (end_parts.skew_x - end_parts.skew_y) - (start_parts.skew_x - start_parts.skew_y),
+            );
+
+            let (scale_x, scale_y) = if self.scale {
+                (
+                    lerp(start_parts.scale_x, end_parts.scale_x, progress.scale),
+                    lerp(start_parts.scale_y, end_parts.scale_y, progress.scale),
+                )
+            } else {
+                (start_parts.scale_x, start_parts.scale_y)
+            };
+            let skew_y = start_parts.skew_y + rotation * progress.rotation;
+            let skew_x =
+                skew_y + (start_parts.skew_x - start_parts.skew_y) + skew * progress.rotation;
+
+            MatrixParts {
+                scale_x,
+                scale_y,
+                skew_x,
+                skew_y,
+            }
+            .to_linear()
+        };
+
+        let (x0, y0) = start.transform_point(transformation_point.x, transformation_point.y);
+        let (x1, y1) = end.transform_point(transformation_point.x, transformation_point.y);
+        let (x, y) = (
+            lerp(x0, x1, progress.position),
+            lerp(y0, y1, progress.position),
+        );
+
+        place_transformation_point((a, b, c, d), transformation_point, x, y)
+    }
+
+    /// Interpolate the matrix of an instance following a motion guide.
+    ///
+    /// The transformation point moves along the path, from the point of the path closest to it at the first keyframe
+    /// to the point closest to it at the next keyframe.
+    /// Instances oriented to the path also turn with the path.
+    pub fn interpolate_matrix_along_path(
+        &self,
+        start: &Matrix,
+        end: &Matrix,
+        transformation_point: &Point,
+        progress: &TweenProgress,
+        path: &GuidePath,
+    ) -> Matrix {
+        let matrix = self.interpolate_matrix(start, end, transformation_point, progress);
+
+        let get_position = |matrix: &Matrix| {
+            let (x, y) = matrix.transform_point(transformation_point.x, transformation_point.y);
+            path.get_closest_position(&Point { x, y })
+        };
+        let start_position = get_position(start);
+        let end_position = get_position(end);
+        let (point, angle) = path.get_point(lerp(start_position, end_position, progress.position));
+
+        let mut linear = (matrix.a, matrix.b, matrix.c, matrix.d);
+        if self.orient_to_path {
+            let (_, start_angle) = path.get_point(start_position);
+            let (s, c) = (angle - start_angle).sin_cos();
+            let (a, b, c_, d) = linear;
+            linear = (c * a - s * b, s * a + c * b, c * c_ - s * d, s * c_ + c * d);
+        }
+
+        place_transformation_point(linear, transformation_point, point.x, point.y)
+    }
+}
+
+/// A motion tween, whose instance is animated by the property curves of a motion object.
+#[derive(Debug, Clone, Copy)]
+pub struct MotionObjectTween<'a> {
+    pub animation_core: &'a AnimationCore,
+}
+
+impl<'a> MotionObjectTween<'a> {
+    /// Get the motion tween starting at a keyframe, if there is one.
+    pub fn from_frame(frame: &'a DomFrame) -> Option<Self> {
+        if !frame.is_motion_object() {
+            return None;
+        }
+
+        Some(Self {
+            animation_core: frame.motion_object_xml.as_ref()?.animation_core.as_ref()?,
+        })
+    }
+
+    /// Get the time of the property curves, `elapsed` frames into a keyframe lasting `duration` frames.
+    pub fn get_time(&self, elapsed: usize, duration: usize, frame_rate: f64) -> f64 {
+        self.animation_core.get_time(elapsed, duration, frame_rate)
+    }
+
+    /// Get the matrix of an instance at a time, from its matrix at the first frame.
+    ///
+    /// The instance is scaled, skewed and rotated around its transformation point, in pixels.
+    pub fn get_matrix(&self, matrix: &Matrix, transformation_point: &Point, time: f64) -> Matrix {
+        let transform = self.animation_core.get_transform(time);
+        let parts = MatrixParts::new(matrix);
+        let rotation = transform.rotation.to_radians();
+
+        let linear = MatrixParts {
+            scale_x: parts.scale_x * transform.scale_x,
+            scale_y: parts.scale_y * transform.scale_y,
+            skew_x: parts.skew_x + rotation + transform.skew_x.to_radians(),
+            skew_y: parts.skew_y + rotation + transform.skew_y.to_radians(),
+        }
+        .to_linear();
+
+        let (x, y) = matrix.transform_point(transformation_point.x, transformation_point.y);
+        place_transformation_point(
+            linear,
+            transformation_point,
+            x + transform.x,
+            y + transform.y,
+        )
+    }
+
+    /// Get the color transform at a time, if the motion tween animates one.
+    pub fn get_color_transform(&self, time: f64) -> Option<ColorTransform> {
+        self.animation_core.get_color_transform(time)
+    }
+
+    /// Animate the filters of an instance at a time.
+    pub fn apply_filters(&self, filters: &mut [Filter], time: f64) {
+        self.animation_core.apply_filters(filters, time)
+    }
+}
+
+/// Make a matrix with a linear part that moves a transformation point, in pixels, to a position.
+fn place_transformation_point(
+    (a, b, c, d): (f64, f64, f64, f64),
+    transformation_point: &Point,
+    x: f64,
+    y: f64,
+) -> Matrix {
+    let mut ret = Matrix::new(a, b, c, d, 0.0, 0.0);
+    let (px, py) = ret.transform_point(transformation_point.x, transformation_point.y);
+    ret.tx = x - px;
+    ret.ty = y - py;
+
+    ret
+}
+
+/// The progress of each property of a classic tween, from 0 at the first keyframe to 1 at the next keyframe.
+///
+/// Eases may overshoot, so progress can be outside of that range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TweenProgress {
+    pub position: f64,
+    pub rotation: f64,
+    pub scale: f64,
+    pub color: f64,
+    pub filters: f64,
+}
+
+impl TweenProgress {
+    /// Make a progress that is the same for every property.
+    pub fn uniform(t: f64) -> Self {
+        Self {
+            position: t,
+            rotation: t,
+            scale: t,
+            color: t,
+            filters: t,
+        }
+    }
+}
+
+/// The ease curves of a tween.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Easing {
+    /// The curves of properties eased separately, including the curve targeting all properties.
+    pub curves: Vec<(EaseTarget, EaseCurve)>,
+
+    /// The curve of properties without a curve, from the `acceleration` of the keyframe.
+    pub fallback: EaseCurve,
+}
+
+impl Easing {
+    /// Get the easing of a tween starting at a keyframe.
+    pub fn from_frame(frame: &DomFrame) -> Self {
+        let acceleration = frame.acceleration.unwrap_or(0.0).clamp(-100.0, 100.0) / 100.0;
+        let fallback = EaseCurve::Classic(acceleration);
+
+        let tweens = match frame.tweens.as_ref() {
+            Some(tweens) if frame.has_custom_ease.unwrap_or(true) => tweens,
+            _ => {
+                return Self {
+                    curves: Vec::new(),
+                    fallback,
+                }
+            }
+        };
+
+        let targets: &[EaseTarget] = if frame.use_single_ease_curve.unwrap_or(false) {
+            &[EaseTarget::All]
+        } else {
+            &[
+                EaseTarget::All,
+                EaseTarget::Position,
+                EaseTarget::Rotation,
+                EaseTarget::Scale,
+                EaseTarget::Color,
+                EaseTarget::Filters,
+            ]
+        };
+
+        Self {
+            curves: targets
+                .iter()
+                .filter_map(|target| Some((*target, tweens.get_curve(*target)?)))
+                .collect(),
+            fallback,
+        }
+    }
+
+    /// Get the curve of a property, falling back to the curve targeting all properties.
+    pub fn get_curve(&self, target: EaseTarget) -> &EaseCurve {
+        let find = |target| {
+            self.curves
+                .iter()
+                .find(|(curve_target, _)| *curve_target == target)
+                .map(|(_, curve)| curve)
+        };
+
+        find(target)
+            .or_else(|| find(EaseTarget::All))
+            .unwrap_or(&self.fallback)
+    }
+}
+
+/// The settings of a shape tween, read from the keyframe starting it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeTween {
+    pub easing: Easing,
+
+    /// The shape hints, pairing points of the first shape with points of the last shape, in pixels.
+    pub hints: Vec<(Point, Point)>,
+}
+
+impl ShapeTween {
+    /// Get the shape tween starting at a keyframe, if there is one.
+    pub fn from_frame(frame: &DomFrame) -> Option<Self> {
+        if !frame.is_shape_tween() {
+            return None;
+        }
+
+        Some(Self {
+            easing: Easing::from_frame(frame),
+            hints: frame
+                .morph_shape
+                .iter()
+                .flat_map(|morph_shape| morph_shape.get_hints())
+                .filter_map(|hint| hint.get_points())
+                .collect(),
+        })
+    }
+
+    /// Get the progress of the tween, `elapsed` frames into a keyframe lasting `duration` frames.
+    pub fn get_progress(&self, elapsed: usize, duration: usize) -> f64 {
+        let t = elapsed as f64 / duration.max(1) as f64;
+        self.easing.get_curve(EaseTarget::All).evaluate(t)
+    }
+
+    /// Get the shape shown between the shape of the first keyframe and the shape of the next keyframe.
+    pub fn morph(&self, start: &DomShape, end: &DomShape, t: f64) -> DomShape {
+        morph_shape(start, end, &self.hints, t)
+    }
+}
+
+/// The scale and skew of a matrix, like the properties of an instance in the editor.
+///
+/// Angles are in radians. A rotation is a skew with equal angles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MatrixParts {
+    scale_x: f64,
+    scale_y: f64,
+    skew_x: f64,
+    skew_y: f64,
+}
+
+impl MatrixParts {
+    fn new(matrix: &Matrix) -> Self {
+        Self {
+            scale_x: matrix.a.hypot(matrix.b),
+            scale_y: matrix.c.hypot(matrix.d),
+            skew_x: (-matrix.c).atan2(matrix.d),
+            skew_y: matrix.b.atan2(matrix.a),
+        }
+    }
+
+    /// Get the `a`, `b`, `c` and `d` components of the matrix.
+    fn to_linear(self) -> (f64, f64, f64, f64) {
+        (
+            self.scale_x * self.skew_y.cos(),
+            self.scale_x * self.skew_y.sin(),
+            -self.scale_y * self.skew_x.sin(),
+            self.scale_y * self.skew_x.cos(),
+        )
+    }
+}
+
+/// Normalize an angle to the range -PI to PI.
+fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(TAU);
+    if angle > PI {
+        angle - TAU
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(matrix: Matrix, expected: Matrix) {
+        let values = |m: Matrix| [m.a, m.b, m.c, m.d, m.tx, m.ty];
+        for (value, expected) in values(matrix).iter().zip(values(expected).iter()) {
+            assert!(
+                (value - expected).abs() < 1e-9,
+                "{:?} != {:?}",
+                matrix,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn interpolate_classic_tween() {
+        let frame: DomFrame = quick_xml::de::from_str(
+            r#"<DOMFrame index="0" duration="10" keyMode="22017" tweenType="motion" acceleration="100"><elements/></DOMFrame>"#,
+        )
+        .unwrap();
+        let tween = MotionTween::from_frame(&frame).unwrap();
+        assert_eq!(tween.get_progress(0, 10), TweenProgress::uniform(0.0));
+        assert_eq!(tween.get_progress(5, 10), TweenProgress::uniform(0.75));
+        assert_eq!(tween.get_progress(10, 10), TweenProgress::uniform(1.0));
+        let half = TweenProgress::uniform(0.5);
+
+        // Scale around the center of a 20px square
+        let center = Point { x: 10.0, y: 10.0 };
+        let start = Matrix::IDENTITY;
+        let end = Matrix::new(3.0, 0.0, 0.0, 3.0, -20.0, -20.0);
+        assert_close(
+            tween.interpolate_matrix(&start, &end, &center, &half),
+            Matrix::new(2.0, 0.0, 0.0, 2.0, -10.0, -10.0),
+        );
+
+        // Rotating by 270 degrees is a quarter turn counter-clockwise, unless turning clockwise
+        let end = Matrix::new(0.0, -1.0, 1.0, 0.0, 0.0, 0.0);
+        let origin = Point::default();
+        let (s, c) = (-PI / 4.0).sin_cos();
+        assert_close(
+            tween.interpolate_matrix(&start, &end, &origin, &half),
+            Matrix::new(c, s, -s, c, 0.0, 0.0),
+        );
+
+        let clockwise = MotionTween {
+            rotate: MotionTweenRotate::Clockwise,
+            rotate_times: 1,
+            ..tween
+        };
+        let (s, c) = (PI * 7.0 / 4.0).sin_cos();
+        assert_close(
+            clockwise.interpolate_matrix(&start, &end, &origin, &half),
+            Matrix::new(c, s, -s, c, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn custom_eases() {
+        let frame: DomFrame = quick_xml::de::from_str(
+            r#"<DOMFrame index="0" duration="4" keyMode="22017" tweenType="motion" acceleration="100" hasCustomEase="true">
+                <tweens>
+                    <Ease target="position" method="quadIn"/>
+                    <Ease target="all" method="none"/>
+                </tweens>
+                <elements/>
+            </DOMFrame>"#,
+        )
+        .unwrap();
+        let progress = MotionTween::from_frame(&frame).unwrap().get_progress(2, 4);
+        assert_eq!(progress.position, 0.25);
+        assert_eq!(progress.rotation, 0.5);
+        assert_eq!(progress.color, 0.5);
+
+        // A single curve eases every property
+        let frame = DomFrame {
+            use_single_ease_curve: Some(true),
+            ..frame
+        };
+        let progress = MotionTween::from_frame(&frame).unwrap().get_progress(2, 4);
+        assert_eq!(progress, TweenProgress::uniform(0.5));
+    }
+}
@@ -1,19 +1,56 @@
+pub mod action_script;
+pub mod color_transform;
+pub mod dom_bitmap_instance;
+pub mod dom_bitmap_item;
 pub mod dom_document;
 pub mod dom_frame;
 pub mod dom_layer;
 pub mod dom_shape;
 pub mod dom_symbol;
+pub mod dom_symbol_instance;
+pub mod dom_text;
 pub mod dom_timeline;
+pub mod ease;
+pub mod filter;
+pub mod matrix;
+pub mod matrix_3d;
+pub mod motion_object;
+pub mod scale_grid;
 
 pub use self::{
+    color_transform::{
+        ColorTransform,
+        Colors,
+    },
+    dom_bitmap_instance::DomBitmapInstance,
+    dom_bitmap_item::DomBitmapItem,
     dom_document::DomDocument,
     dom_frame::DomFrame,
     dom_layer::DomLayer,
     dom_shape::DomShape,
     dom_symbol::DomSymbol,
+    dom_symbol_instance::DomSymbolInstance,
+    dom_text::DomText,
     dom_timeline::DomTimeline,
+    filter::{
+        Filter,
+        Filters,
+    },
+    matrix::{
+        Matrices,
+        Matrix,
+        Point,
+    },
+    matrix_3d::{
+        Matrices3D,
+        Matrix3D,
+        Perspective,
+    },
+};
+use crate::{
+    xml,
+    FlaResult,
 };
-use crate::FlaResult;
 use std::{
     io::{
         BufRead,
@@ -42,7 +79,7 @@ pub struct DomFontItem {
 
 #[derive(Debug)]
 pub enum LibraryEntry {
-    Xml(DomSymbol),
+    Xml(Box<DomSymbol>),
     Unknown(Vec<u8>),
 }
 
@@ -54,8 +91,11 @@ impl LibraryEntry {
             .as_deref()
         {
             Some("xml") => {
-                let dom_symbol: DomSymbol = quick_xml::de::from_reader(reader)?;
-                Ok(Self::Xml(dom_symbol))
+                let mut xml = String::new();
+                reader.read_to_string(&mut xml)?;
+
+                let dom_symbol: DomSymbol = xml::from_str(&xml)?;
+                Ok(Self::Xml(Box::new(dom_symbol)))
             }
             Some(_) | None => {
                 let mut entry = Vec::new();
@@ -73,3 +113,23 @@ impl LibraryEntry {
         }
     }
 }
+
+/// Interpolate between `start`, at `t` = 0, and `end`, at `t` = 1.
+pub(crate) fn lerp(start: f64, end: f64, t: f64) -> f64 {
+    start + (end - start) * t
+}
+
+/// Interpolate between two colors, channel by channel.
+pub(crate) fn lerp_rgb(start: (u8, u8, u8), end: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let channel = |start: u8, end: u8| {
+        lerp(f64::from(start), f64::from(end), t)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    };
+
+    (
+        channel(start.0, end.0),
+        channel(start.1, end.1),
+        channel(start.2, end.2),
+    )
+}
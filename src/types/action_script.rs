@@ -0,0 +1,109 @@
+/// The `<Actionscript>` element of a keyframe.
+#[derive(Debug, serde::Deserialize)]
+pub struct ActionScript {
+    /// The source of the script, stored as CDATA.
+    #[serde(deserialize_with = "deserialize_cdata")]
+    pub script: String,
+}
+
+/// Deserialize CDATA as is. Sources contain characters like `&` that must not be unescaped.
+fn deserialize_cdata<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct CdataVisitor;
+
+    impl serde::de::Visitor<'_> for CdataVisitor {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("script source")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<String, E> {
+            String::from_utf8(value.to_vec()).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_bytes(CdataVisitor)
+}
+
+/// A script of a keyframe, with its location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameScript<'a> {
+    /// The name of the symbol holding the script, or `None` for a scene.
+    pub symbol: Option<&'a str>,
+
+    /// The name of the timeline holding the script.
+    pub timeline: &'a str,
+
+    pub layer_index: usize,
+    pub layer_name: &'a str,
+
+    /// The first frame of the keyframe, 0-indexed.
+    pub frame_index: usize,
+
+    pub source: &'a str,
+}
+
+impl<'a> FrameScript<'a> {
+    /// Get the paths of the files included with `#include "path"`.
+    pub fn get_includes(&self) -> Vec<&'a str> {
+        get_includes(self.source)
+    }
+}
+
+/// Get the paths of the files included by a script with `#include "path"`.
+pub fn get_includes(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("#include")?.trim_start();
+            let rest = rest.strip_prefix('"')?;
+            Some(&rest[..rest.find('"')?])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::DomTimeline;
+
+    #[test]
+    fn frame_scripts() {
+        let timeline: DomTimeline = quick_xml::de::from_str(
+            r##"<DOMTimeline name="Scene 1">
+                <layers>
+                    <DOMLayer name="actions" color="#000000">
+                        <frames>
+                            <DOMFrame index="0" duration="5" keyMode="9728">
+                                <Actionscript>
+                                    <script><![CDATA[#include "lib/utils.as"
+if (a < b && ready) { stop(); }]]></script>
+                                </Actionscript>
+                                <elements/>
+                            </DOMFrame>
+                            <DOMFrame index="5" keyMode="9728"><elements/></DOMFrame>
+                        </frames>
+                    </DOMLayer>
+                </layers>
+            </DOMTimeline>"##,
+        )
+        .unwrap();
+
+        let scripts = timeline.get_scripts();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].timeline, "Scene 1");
+        assert_eq!(scripts[0].layer_name, "actions");
+        assert_eq!(scripts[0].frame_index, 0);
+        assert_eq!(
+            scripts[0].source,
+            "#include \"lib/utils.as\"\nif (a < b && ready) { stop(); }"
+        );
+        assert_eq!(scripts[0].get_includes(), ["lib/utils.as"]);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::types::dom_shape::parse_rgb;
+
+/// The color effect of an instance, as stored in the XFL.
+///
+/// Brightness and tint are stored as they are shown in the editor, and are converted to multipliers and offsets.
+/// Offsets range from -255 to 255.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct Color {
+    #[serde(rename = "alphaMultiplier")]
+    pub alpha_multiplier: Option<f64>,
+
+    #[serde(rename = "redMultiplier")]
+    pub red_multiplier: Option<f64>,
+
+    #[serde(rename = "greenMultiplier")]
+    pub green_multiplier: Option<f64>,
+
+    #[serde(rename = "blueMultiplier")]
+    pub blue_multiplier: Option<f64>,
+
+    #[serde(rename = "alphaOffset")]
+    pub alpha_offset: Option<f64>,
+
+    #[serde(rename = "redOffset")]
+    pub red_offset: Option<f64>,
+
+    #[serde(rename = "greenOffset")]
+    pub green_offset: Option<f64>,
+
+    #[serde(rename = "blueOffset")]
+    pub blue_offset: Option<f64>,
+
+    /// The brightness, from -1 (black) to 1 (white).
+    pub brightness: Option<f64>,
+
+    #[serde(rename = "tintColor")]
+    pub tint_color: Option<String>,
+
+    /// The amount of tint, from 0 to 1.
+    #[serde(rename = "tintMultiplier")]
+    pub tint_multiplier: Option<f64>,
+}
+
+impl Color {
+    /// Get the color transform of this color effect.
+    pub fn get_color_transform(&self) -> ColorTransform {
+        let mut ret = ColorTransform {
+            multipliers: [
+                self.red_multiplier.unwrap_or(1.0),
+                self.green_multiplier.unwrap_or(1.0),
+                self.blue_multiplier.unwrap_or(1.0),
+                self.alpha_multiplier.unwrap_or(1.0),
+            ],
+            offsets: [
+                self.red_offset.unwrap_or(0.0),
+                self.green_offset.unwrap_or(0.0),
+                self.blue_offset.unwrap_or(0.0),
+                self.alpha_offset.unwrap_or(0.0),
+            ],
+        };
+
+        if let Some(brightness) = self.brightness.filter(|brightness| *brightness != 0.0) {
+            let brightness = brightness.clamp(-1.0, 1.0);
+            let offset = if brightness > 0.0 {
+                brightness * 255.0
+            } else {
+                0.0
+            };
+            for i in 0..3 {
+                ret.multipliers[i] = 1.0 - brightness.abs();
+                ret.offsets[i] = offset;
+            }
+        }
+
+        let tint = self
+            .tint_color
+            .as_deref()
+            .and_then(parse_rgb)
+            .zip(self.tint_multiplier);
+        if let Some(((r, g, b), tint_multiplier)) = tint {
+            for (i, channel) in [r, g, b].iter().enumerate() {
+                ret.multipliers[i] = 1.0 - tint_multiplier;
+                ret.offsets[i] = f64::from(*channel) * tint_multiplier;
+            }
+        }
+
+        ret
+    }
+}
+
+/// The `<color>` wrapper element.
+#[derive(Debug, serde::Deserialize)]
+pub struct Colors {
+    #[serde(rename = "Color", default)]
+    pub color: Vec<Color>,
+}
+
+impl Colors {
+    /// Get the color transform, or the identity if it is missing.
+    pub fn get_color_transform(&self) -> ColorTransform {
+        self.color
+            .first()
+            .map(|color| color.get_color_transform())
+            .unwrap_or_default()
+    }
+}
+
+/// A transform applied to every color of an instance.
+///
+/// Each channel is mapped to `channel * multiplier + offset`, where channels range from 0 to 255.
+/// Channels are ordered red, green, blue, alpha.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorTransform {
+    pub multipliers: [f64; 4],
+    pub offsets: [f64; 4],
+}
+
+impl ColorTransform {
+    /// The identity color transform.
+    pub const IDENTITY: Self = Self {
+        multipliers: [1.0; 4],
+        offsets: [0.0; 4],
+    };
+
+    /// Get a color transform that applies `self`, then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut ret = Self::IDENTITY;
+        for i in 0..4 {
+            ret.multipliers[i] = self.multipliers[i] * other.multipliers[i];
+            ret.offsets[i] = self.offsets[i] * other.multipliers[i] + other.offsets[i];
+        }
+
+        ret
+    }
+
+    /// Interpolate between this color transform, at `t` = 0, and `other`, at `t` = 1.
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let mut ret = *self;
+        for i in 0..4 {
+            ret.multipliers[i] += (other.multipliers[i] - self.multipliers[i]) * t;
+            ret.offsets[i] += (other.offsets[i] - self.offsets[i]) * t;
+        }
+
+        ret
+    }
+
+    /// Check if this is the identity color transform.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Transform a color, in the order red, green, blue, alpha.
+    pub fn apply(&self, rgba: [u8; 4]) -> [u8; 4] {
+        let mut ret = [0; 4];
+        for i in 0..4 {
+            let channel = f64::from(rgba[i]) * self.multipliers[i] + self.offsets[i];
+            ret[i] = channel.round().clamp(0.0, 255.0) as u8;
+        }
+
+        ret
+    }
+
+    /// Transform a premultiplied ARGB pixel, like the pixels of a [`crate::bitmap::Bitmap`].
+    pub fn apply_premultiplied_argb(&self, pixel: u32) -> u32 {
+        let [a, r, g, b] = pixel.to_be_bytes();
+        let unpremultiply = |channel: u8| {
+            if a == 0 {
+                0
+            } else {
+                ((u32::from(channel) * 255 + u32::from(a) / 2) / u32::from(a)).min(255) as u8
+            }
+        };
+
+        let [r, g, b, a] = self.apply([unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+        let premultiply = |channel: u8| ((u32::from(channel) * u32::from(a) + 127) / 255) as u8;
+
+        u32::from_be_bytes([a, premultiply(r), premultiply(g), premultiply(b)])
+    }
+}
+
+impl Default for ColorTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(xml: &str) -> ColorTransform {
+        quick_xml::de::from_str::<Color>(xml)
+            .unwrap()
+            .get_color_transform()
+    }
+
+    #[test]
+    fn color_effects() {
+        let alpha = parse(r#"<Color alphaMultiplier="0.5"/>"#);
+        assert_eq!(alpha.apply([200, 100, 0, 255]), [200, 100, 0, 128]);
+
+        let tint = parse(r##"<Color tintMultiplier="0.5" tintColor="#FF0000"/>"##);
+        assert_eq!(tint.apply([0, 100, 200, 255]), [128, 50, 100, 255]);
+
+        let brighter = parse(r#"<Color brightness="0.5"/>"#);
+        assert_eq!(brighter.apply([0, 100, 255, 255]), [128, 178, 255, 255]);
+
+        let darker = parse(r#"<Color brightness="-0.5"/>"#);
+        assert_eq!(darker.apply([0, 100, 255, 255]), [0, 50, 128, 255]);
+
+        let advanced = parse(r#"<Color redMultiplier="0" greenOffset="-50" alphaOffset="255"/>"#);
+        assert_eq!(advanced.apply([100, 100, 100, 0]), [0, 50, 100, 255]);
+    }
+
+    #[test]
+    fn compose() {
+        let half = parse(r#"<Color alphaMultiplier="0.5" redOffset="10"/>"#);
+        let composed = half.then(&half);
+        assert_eq!(composed.multipliers[3], 0.25);
+        assert_eq!(composed.offsets[0], 20.0);
+        assert!(ColorTransform::IDENTITY.then(&half) == half);
+        assert_eq!(
+            ColorTransform::IDENTITY.interpolate(&half, 0.5).multipliers[3],
+            0.75
+        );
+
+        assert_eq!(half.apply_premultiplied_argb(0xFF_80_00_00), 0x80_45_00_00);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    types::{
+        Matrices,
+        Matrix,
+    },
+    Fla,
+};
+
+/// An instance of a library bitmap placed in a frame.
+#[derive(Debug, serde::Deserialize)]
+pub struct DomBitmapInstance {
+    #[serde(rename = "libraryItemName")]
+    pub library_item_name: String,
+
+    pub name: Option<String>,
+    pub selected: Option<bool>,
+
+    #[serde(rename = "matrix")]
+    pub matrices: Option<Matrices>,
+}
+
+impl DomBitmapInstance {
+    /// Get the matrix of this instance, or the identity if it is missing.
+    pub fn get_matrix(&self) -> Matrix {
+        self.matrices
+            .as_ref()
+            .map(|matrices| matrices.get_matrix())
+            .unwrap_or_default()
+    }
+
+    pub fn calc_bounding_box(&self, fla: &Fla) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        let bounding_box = fla
+            .get_bitmap_item(&self.library_item_name)?
+            .calc_bounding_box()?;
+
+        Some(self.get_matrix().transform_twips_box(&bounding_box))
+    }
+}
@@ -0,0 +1,44 @@
+/// A bitmap in the media library.
+#[derive(Debug, serde::Deserialize)]
+pub struct DomBitmapItem {
+    pub name: String,
+
+    #[serde(rename = "itemID")]
+    pub item_id: Option<String>,
+
+    #[serde(rename = "sourceExternalFilepath")]
+    pub source_external_filepath: Option<String>,
+
+    #[serde(rename = "sourceLastImported")]
+    pub source_last_imported: Option<u64>,
+
+    #[serde(rename = "originalCompressionType")]
+    pub original_compression_type: Option<String>,
+
+    pub quality: Option<u64>,
+    pub href: Option<String>,
+
+    /// The name of the file in the `bin` folder holding the bitmap data.
+    #[serde(rename = "bitmapDataHRef")]
+    pub bitmap_data_href: Option<String>,
+
+    #[serde(rename = "allowSmoothing")]
+    pub allow_smoothing: Option<bool>,
+
+    /// The width of the bitmap, in twips.
+    #[serde(rename = "frameRight")]
+    pub frame_right: Option<f64>,
+
+    /// The height of the bitmap, in twips.
+    #[serde(rename = "frameBottom")]
+    pub frame_bottom: Option<f64>,
+}
+
+impl DomBitmapItem {
+    pub fn calc_bounding_box(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        Some(euclid::Box2D::new(
+            euclid::Point2D::new(0.0, 0.0),
+            euclid::Point2D::new(self.frame_right?, self.frame_bottom?),
+        ))
+    }
+}
@@ -1,6 +1,8 @@
 use crate::types::{
+    DomBitmapItem,
     DomFontItem,
     DomTimeline,
+    Perspective,
 };
 
 #[derive(Debug, serde::Deserialize)]
@@ -79,6 +81,13 @@ pub struct DomDocument {
     pub swc_cache: SwcCache,
 }
 
+impl DomDocument {
+    /// Get the perspective projection of 3D instances, from the stage size and `viewAngle3D`.
+    pub fn get_perspective(&self) -> Perspective {
+        Perspective::new(self.width as f64, self.height as f64, self.view_angle_3d)
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct Fonts {
     #[serde(rename = "DOMFontItem", default)]
@@ -86,7 +95,28 @@ pub struct Fonts {
 }
 
 #[derive(Debug, serde::Deserialize)]
-pub struct Media {}
+pub struct Media {
+    #[serde(rename = "$value", default)]
+    pub media_items: Vec<MediaItem>,
+}
+
+impl Media {
+    pub fn get_bitmap_items(&self) -> impl Iterator<Item = &DomBitmapItem> {
+        self.media_items.iter().filter_map(|item| match item {
+            MediaItem::Bitmap(bitmap_item) => Some(&**bitmap_item),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub enum MediaItem {
+    #[serde(rename = "DOMBitmapItem")]
+    Bitmap(Box<DomBitmapItem>),
+
+    #[serde(other)]
+    Unknown,
+}
 
 #[derive(Debug, serde::Deserialize)]
 pub struct Symbols {
@@ -118,7 +148,43 @@ pub struct Timelines {
 }
 
 #[derive(Debug, serde::Deserialize)]
-pub struct Scripts {}
+pub struct Scripts {
+    #[serde(rename = "$value", default)]
+    pub items: Vec<ScriptItem>,
+}
+
+impl Scripts {
+    /// Get the paths of the external script files referenced by the document.
+    pub fn get_includes(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().filter_map(|item| match item {
+            ScriptItem::Include(include) => Some(include.href.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub enum ScriptItem {
+    #[serde(rename = "GlobalScripts")]
+    GlobalScripts(GlobalScripts),
+
+    #[serde(rename = "Include")]
+    Include(ScriptInclude),
+
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct GlobalScripts {
+    pub language: Option<String>,
+}
+
+/// An external `.as` file referenced by the document.
+#[derive(Debug, serde::Deserialize)]
+pub struct ScriptInclude {
+    pub href: String,
+}
 
 #[derive(Debug, serde::Deserialize)]
 pub struct PersistentData {}
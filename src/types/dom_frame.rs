@@ -1,23 +1,157 @@
-use crate::types::DomShape;
+use crate::{
+    types::{
+        action_script::ActionScript,
+        dom_shape::morph::MorphShape,
+        dom_timeline::LayerPolicy,
+        ease::Tweens,
+        matrix::TWIPS_PER_PIXEL,
+        motion_object::MotionObjectXml,
+        DomBitmapInstance,
+        DomShape,
+        DomSymbolInstance,
+        DomText,
+    },
+    Fla,
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct DomFrame {
+    /// The index of the first frame of this keyframe, 0-indexed.
     pub index: u64,
 
+    /// The number of frames this keyframe lasts. Missing durations are 1.
+    pub duration: Option<u64>,
+
     #[serde(rename = "keyMode")]
     pub key_mode: u64,
 
+    /// The label of this keyframe.
+    pub name: Option<String>,
+
+    #[serde(rename = "labelType")]
+    pub label_type: Option<LabelType>,
+
+    #[serde(rename = "tweenType")]
+    pub tween_type: Option<TweenType>,
+
+    /// The easing of a classic tween, from -100 (ease in) to 100 (ease out).
+    pub acceleration: Option<f64>,
+
+    #[serde(rename = "motionTweenRotate")]
+    pub motion_tween_rotate: Option<MotionTweenRotate>,
+
+    /// The number of full turns added by a clockwise or counter-clockwise rotation.
+    #[serde(rename = "motionTweenRotateTimes")]
+    pub motion_tween_rotate_times: Option<u64>,
+
+    #[serde(rename = "motionTweenScale")]
+    pub motion_tween_scale: Option<bool>,
+
+    #[serde(rename = "motionTweenOrientToPath")]
+    pub motion_tween_orient_to_path: Option<bool>,
+
+    /// Whether the eases in `tweens` replace `acceleration`.
+    #[serde(rename = "hasCustomEase")]
+    pub has_custom_ease: Option<bool>,
+
+    /// Whether the ease targeting all properties is used for every property.
+    #[serde(rename = "useSingleEaseCurve")]
+    pub use_single_ease_curve: Option<bool>,
+
+    /// The eases of a tween, which may target separate properties.
+    pub tweens: Option<Tweens>,
+
+    /// The property curves of a motion tween.
+    #[serde(rename = "motionObjectXML")]
+    pub motion_object_xml: Option<MotionObjectXml>,
+
+    /// The shape hints of a shape tween.
+    #[serde(rename = "morphShape")]
+    pub morph_shape: Option<MorphShape>,
+
+    #[serde(rename = "Actionscript")]
+    pub action_script: Option<ActionScript>,
+
     pub elements: Elements,
 }
 
 impl DomFrame {
-    pub fn calc_bounding_box(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+    /// Get the number of frames this keyframe lasts.
+    pub fn get_duration(&self) -> usize {
+        self.duration.unwrap_or(1).max(1) as usize
+    }
+
+    /// Get the index of the frame after the last frame of this keyframe.
+    pub fn get_end(&self) -> usize {
+        self.index as usize + self.get_duration()
+    }
+
+    /// Check if this keyframe starts a classic motion tween.
+    pub fn is_motion_tween(&self) -> bool {
+        self.tween_type == Some(TweenType::Motion)
+    }
+
+    /// Check if this keyframe starts a motion tween, described by a motion object.
+    pub fn is_motion_object(&self) -> bool {
+        self.tween_type == Some(TweenType::MotionObject)
+    }
+
+    /// Check if this keyframe starts a shape tween.
+    pub fn is_shape_tween(&self) -> bool {
+        self.tween_type == Some(TweenType::Shape)
+    }
+
+    /// Get how a classic tween rotates. Tweens without a rotation rotate the shortest way.
+    pub fn get_motion_tween_rotate(&self) -> MotionTweenRotate {
+        self.motion_tween_rotate.unwrap_or(MotionTweenRotate::Auto)
+    }
+
+    /// Get the label of this keyframe and its kind, if it has one.
+    ///
+    /// Labels without a kind are frame labels.
+    pub fn get_label(&self) -> Option<(&str, LabelType)> {
+        let name = self.name.as_deref().filter(|name| !name.is_empty())?;
+        Some((name, self.label_type.unwrap_or(LabelType::Name)))
+    }
+
+    /// Get the source of the script of this keyframe, if it has one.
+    pub fn get_script(&self) -> Option<&str> {
+        Some(self.action_script.as_ref()?.script.as_str())
+    }
+
+    /// Check if this keyframe is shown at a frame, 0-indexed.
+    pub fn contains(&self, frame_index: usize) -> bool {
+        (self.index as usize..self.get_end()).contains(&frame_index)
+    }
+
+    /// Calculate the bounding box of this keyframe, in a symbol nested in the symbols named by `ancestors`.
+    ///
+    /// If `include_filters` is set, the bounding box includes the area drawn by filters of symbol instances.
+    pub fn calc_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        ancestors: &[&str],
+        include_filters: bool,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        // Motion tweens move their elements, so include the bounds at each keyframe of the motion.
+        let offsets = self
+            .motion_object_xml
+            .as_ref()
+            .and_then(|motion_object_xml| motion_object_xml.animation_core.as_ref())
+            .map(|animation_core| animation_core.get_keyframe_offsets())
+            .unwrap_or_default();
+
         let mut ret = None;
         for bounding_box in self
-            .get_shapes()
+            .get_elements()
             .iter()
-            .map(|s| s.calc_bounding_box())
-            .flatten()
+            .filter_map(|e| e.calc_bounding_box(fla, layer_policy, ancestors, include_filters))
+            .flat_map(|bounding_box| {
+                std::iter::once(bounding_box).chain(offsets.iter().map(move |(x, y)| {
+                    bounding_box.translate(euclid::vec2(x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL))
+                }))
+            })
         {
             let bb = ret.get_or_insert(bounding_box);
             bb.min.x = bb.min.x.min(bounding_box.min.x);
@@ -29,13 +163,202 @@ impl DomFrame {
         ret
     }
 
-    pub fn get_shapes(&self) -> &[DomShape] {
-        &self.elements.dom_shapes
+    /// Get the elements of this frame, from bottom to top.
+    pub fn get_elements(&self) -> &[Element] {
+        &self.elements.elements
+    }
+
+    pub fn get_shapes(&self) -> impl Iterator<Item = &DomShape> {
+        self.get_elements().iter().filter_map(|e| e.as_shape())
+    }
+
+    pub fn get_bitmap_instances(&self) -> impl Iterator<Item = &DomBitmapInstance> {
+        self.get_elements()
+            .iter()
+            .filter_map(|e| e.as_bitmap_instance())
+    }
+
+    pub fn get_texts(&self) -> impl Iterator<Item = &DomText> {
+        self.get_elements().iter().filter_map(|e| e.as_text())
+    }
+
+    pub fn get_symbol_instances(&self) -> impl Iterator<Item = &DomSymbolInstance> {
+        self.get_elements()
+            .iter()
+            .filter_map(|e| e.as_symbol_instance())
     }
 }
 
+/// The kind of tween starting at a keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum TweenType {
+    /// A classic tween.
+    #[serde(rename = "motion")]
+    Motion,
+
+    #[serde(rename = "shape")]
+    Shape,
+
+    /// A motion tween, described by a motion object.
+    #[serde(rename = "motion object")]
+    MotionObject,
+}
+
+/// The kind of a keyframe label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum LabelType {
+    /// A frame label, which names a frame.
+    #[serde(rename = "name")]
+    Name,
+
+    /// A comment, which is only shown in the editor.
+    #[serde(rename = "comment")]
+    Comment,
+
+    /// A frame label that is also a named anchor for browser navigation.
+    #[serde(rename = "anchor")]
+    Anchor,
+}
+
+/// How a classic tween rotates its instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum MotionTweenRotate {
+    /// The transform is interpolated without rotating.
+    #[serde(rename = "none")]
+    None,
+
+    /// Rotate the shortest way.
+    #[serde(rename = "auto")]
+    Auto,
+
+    #[serde(rename = "clockwise")]
+    Clockwise,
+
+    #[serde(rename = "counter-clockwise")]
+    CounterClockwise,
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct Elements {
-    #[serde(rename = "DOMShape", default)]
-    pub dom_shapes: Vec<DomShape>,
+    #[serde(rename = "$value", default)]
+    pub elements: Vec<Element>,
+}
+
+/// An element placed in a frame.
+#[derive(Debug, serde::Deserialize)]
+pub enum Element {
+    #[serde(rename = "DOMShape")]
+    Shape(DomShape),
+
+    #[serde(rename = "DOMSymbolInstance")]
+    SymbolInstance(DomSymbolInstance),
+
+    #[serde(rename = "DOMBitmapInstance")]
+    BitmapInstance(DomBitmapInstance),
+
+    #[serde(rename = "DOMStaticText")]
+    StaticText(DomText),
+
+    #[serde(rename = "DOMDynamicText")]
+    DynamicText(DomText),
+
+    #[serde(rename = "DOMInputText")]
+    InputText(DomText),
+
+    #[serde(other)]
+    Unknown,
+}
+
+impl Element {
+    pub fn as_shape(&self) -> Option<&DomShape> {
+        match self {
+            Self::Shape(shape) => Some(shape),
+            _ => None,
+        }
+    }
+
+    pub fn as_symbol_instance(&self) -> Option<&DomSymbolInstance> {
+        match self {
+            Self::SymbolInstance(symbol_instance) => Some(symbol_instance),
+            _ => None,
+        }
+    }
+
+    pub fn as_bitmap_instance(&self) -> Option<&DomBitmapInstance> {
+        match self {
+            Self::BitmapInstance(bitmap_instance) => Some(bitmap_instance),
+            _ => None,
+        }
+    }
+
+    /// Get the text field of this element, if it is static, dynamic or input text.
+    pub fn as_text(&self) -> Option<&DomText> {
+        match self {
+            Self::StaticText(text) | Self::DynamicText(text) | Self::InputText(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Calculate the bounding box of this element, in a symbol nested in the symbols named by `ancestors`.
+    ///
+    /// If `include_filters` is set, the bounding box includes the area drawn by filters of symbol instances.
+    pub fn calc_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        ancestors: &[&str],
+        include_filters: bool,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        match self {
+            Self::Shape(shape) => shape.calc_bounding_box(),
+            Self::SymbolInstance(symbol_instance) => {
+                symbol_instance.calc_bounding_box(fla, layer_policy, ancestors, include_filters)
+            }
+            Self::BitmapInstance(bitmap_instance) => bitmap_instance.calc_bounding_box(fla),
+            Self::StaticText(text) | Self::DynamicText(text) | Self::InputText(text) => {
+                text.calc_bounding_box()
+            }
+            Self::Unknown => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::dom_symbol_instance::{
+        LoopMode,
+        SymbolType,
+    };
+
+    const FRAME: &str = r#"<DOMFrame index="0" keyMode="9728">
+    <elements>
+        <DOMShape><edges><Edge fillStyle1="1" edges="!0 0|20 0|20 20"/></edges></DOMShape>
+        <DOMSymbolInstance libraryItemName="Head" symbolType="graphic" loop="play once" firstFrame="3">
+            <matrix><Matrix a="0.5" d="0.5" tx="12" ty="-4"/></matrix>
+            <transformationPoint><Point x="10" y="5"/></transformationPoint>
+        </DOMSymbolInstance>
+        <DOMTLFText/>
+    </elements>
+</DOMFrame>"#;
+
+    #[test]
+    fn parse_elements_in_order() {
+        let frame: DomFrame = quick_xml::de::from_str(FRAME).unwrap();
+        let elements = frame.get_elements();
+        assert_eq!(elements.len(), 3);
+        assert!(elements[0].as_shape().is_some());
+        assert!(matches!(elements[2], Element::Unknown));
+
+        let symbol_instance = elements[1].as_symbol_instance().unwrap();
+        assert_eq!(symbol_instance.library_item_name, "Head");
+        assert_eq!(symbol_instance.get_symbol_type(), SymbolType::Graphic);
+        assert_eq!(symbol_instance.get_loop_mode(), LoopMode::PlayOnce);
+        assert_eq!(symbol_instance.get_first_frame(), 3);
+        assert_eq!(
+            symbol_instance.get_matrix().transform_point(2.0, 2.0),
+            (13.0, -3.0)
+        );
+        assert_eq!(symbol_instance.get_transformation_point().x, 10.0);
+    }
 }
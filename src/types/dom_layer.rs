@@ -1,4 +1,8 @@
 pub use crate::types::DomFrame;
+use crate::{
+    types::dom_timeline::LayerPolicy,
+    Fla,
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct DomLayer {
@@ -12,6 +16,24 @@ pub struct DomLayer {
     #[serde(rename = "autoNamed")]
     pub auto_named: Option<bool>,
 
+    /// Whether this layer is shown in the editor. Missing values are `true`.
+    pub visible: Option<bool>,
+
+    pub locked: Option<bool>,
+
+    /// Whether this layer is shown as outlines in the editor.
+    pub outline: Option<bool>,
+
+    /// Whether this folder is expanded in the editor.
+    pub open: Option<bool>,
+
+    #[serde(rename = "layerType")]
+    pub layer_type: Option<LayerType>,
+
+    /// The index of the layer containing this layer, like the folder holding it or the guide guiding it.
+    #[serde(rename = "parentLayerIndex")]
+    pub parent_layer_index: Option<usize>,
+
     pub frames: Frames,
 }
 
@@ -20,13 +42,61 @@ impl DomLayer {
         &self.frames.dom_frames
     }
 
-    pub fn calc_bounding_box(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+    /// Get the kind of this layer. Layers without a type are normal layers.
+    pub fn get_layer_type(&self) -> LayerType {
+        self.layer_type.unwrap_or(LayerType::Normal)
+    }
+
+    /// Check if this is a guide layer, which is not rendered.
+    pub fn is_guide(&self) -> bool {
+        self.get_layer_type() == LayerType::Guide
+    }
+
+    /// Check if this is a folder, which holds other layers.
+    pub fn is_folder(&self) -> bool {
+        self.get_layer_type() == LayerType::Folder
+    }
+
+    /// Check if this is a mask layer, masking the layers below it.
+    pub fn is_mask(&self) -> bool {
+        self.get_layer_type() == LayerType::Mask
+    }
+
+    /// Check if this layer is shown in the editor, ignoring the folders holding it.
+    pub fn is_visible(&self) -> bool {
+        self.visible.unwrap_or(true)
+    }
+
+    /// Get the keyframe shown at a frame, 0-indexed.
+    ///
+    /// Returns `None` if the layer has no keyframe spanning that frame.
+    pub fn frame_at(&self, frame_index: usize) -> Option<&DomFrame> {
+        let frames = self.get_frames();
+        let position = frames.partition_point(|frame| frame.index as usize <= frame_index);
+        let frame = &frames[position.checked_sub(1)?];
+
+        if frame.contains(frame_index) {
+            Some(frame)
+        } else {
+            None
+        }
+    }
+
+    /// Calculate the bounding box of this layer, in a symbol nested in the symbols named by `ancestors`.
+    ///
+    /// If `include_filters` is set, the bounding box includes the area drawn by filters of symbol instances.
+    pub fn calc_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        ancestors: &[&str],
+        include_filters: bool,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
         let mut ret = None;
         for bounding_box in self
             .get_frames()
             .iter()
-            .map(|f| f.calc_bounding_box())
-            .flatten()
+            .filter_map(|f| f.calc_bounding_box(fla, layer_policy, ancestors, include_filters))
         {
             let bb = ret.get_or_insert(bounding_box);
             bb.min.x = bb.min.x.min(bounding_box.min.x);
@@ -38,13 +108,78 @@ impl DomLayer {
         ret
     }
 
+    /// Get the number of frames in this layer, including the frames spanned by keyframes.
     pub fn num_frames(&self) -> usize {
-        self.get_frames().len()
+        self.get_frames()
+            .last()
+            .map(|frame| frame.get_end())
+            .unwrap_or(0)
     }
 }
 
+/// The kind of a layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum LayerType {
+    #[serde(rename = "normal")]
+    Normal,
+
+    /// A layer that is only shown in the editor.
+    ///
+    /// Guide layers with guided layers are motion guides, whose strokes are paths for the classic tweens of the guided layers.
+    #[serde(rename = "guide")]
+    Guide,
+
+    /// A layer following a motion guide.
+    #[serde(rename = "guided")]
+    Guided,
+
+    /// A layer whose content masks the layers it holds.
+    #[serde(rename = "mask")]
+    Mask,
+
+    /// A layer masked by a mask layer.
+    #[serde(rename = "masked")]
+    Masked,
+
+    /// A folder, holding other layers. Folders have no frames of their own.
+    #[serde(rename = "folder")]
+    Folder,
+
+    /// The layer of the camera tool, which has no content.
+    #[serde(rename = "camera")]
+    Camera,
+
+    #[serde(other)]
+    Unknown,
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct Frames {
     #[serde(rename = "DOMFrame", default)]
     pub dom_frames: Vec<DomFrame>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_at_uses_durations() {
+        let layer: DomLayer = quick_xml::de::from_str(
+            r##"<DOMLayer name="a" color="#000000"><frames>
+                <DOMFrame index="0" duration="10" keyMode="9728"><elements/></DOMFrame>
+                <DOMFrame index="10" keyMode="9728"><elements/></DOMFrame>
+                <DOMFrame index="15" duration="5" keyMode="9728"><elements/></DOMFrame>
+            </frames></DOMLayer>"##,
+        )
+        .unwrap();
+
+        assert_eq!(layer.num_frames(), 20);
+        assert_eq!(layer.frame_at(0).unwrap().index, 0);
+        assert_eq!(layer.frame_at(9).unwrap().index, 0);
+        assert_eq!(layer.frame_at(10).unwrap().index, 10);
+        assert!(layer.frame_at(11).is_none());
+        assert_eq!(layer.frame_at(19).unwrap().index, 15);
+        assert!(layer.frame_at(20).is_none());
+    }
+}
@@ -1,12 +1,18 @@
 pub mod edge;
+pub mod morph;
+pub mod path;
 
 pub use self::edge::{
     Edge,
     EdgeDefinitionCommand,
     SelectionMask,
 };
+pub use crate::types::{
+    Matrices,
+    Matrix,
+};
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct DomShape {
     pub selected: Option<bool>,
 
@@ -37,6 +43,14 @@ impl DomShape {
             .find(|el| el.index == Some(index))
     }
 
+    /// Get the matrix of this shape, or the identity if it is missing.
+    pub fn get_matrix(&self) -> Matrix {
+        self.matrices
+            .as_ref()
+            .map(|matrices| matrices.get_matrix())
+            .unwrap_or_default()
+    }
+
     pub fn get_edges(&self) -> &[Edge] {
         &self.edges.edges
     }
@@ -51,8 +65,7 @@ impl DomShape {
             for cmd in edge
                 .edges
                 .iter()
-                .map(|edge_def| edge_def.commands.iter())
-                .flatten()
+                .flat_map(|edge_def| edge_def.commands.iter())
             {
                 match cmd {
                     EdgeDefinitionCommand::MoveTo(x, y)
@@ -74,34 +87,22 @@ impl DomShape {
             }
         }
 
-        Some(euclid::Box2D::new(
+        let bounding_box = euclid::Box2D::new(
             euclid::Point2D::new(min_x?, min_y?),
             euclid::Point2D::new(max_x?, max_y?),
-        ))
-    }
-}
+        );
 
-#[derive(Debug, serde::Deserialize)]
-pub struct Matrices {
-    #[serde(rename = "Matrix", default)]
-    pub matrix: Vec<Matrix>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct Matrix {
-    pub a: Option<f64>,
-    pub d: Option<f64>,
-    pub tx: Option<f64>,
-    pub ty: Option<f64>,
+        Some(self.get_matrix().transform_twips_box(&bounding_box))
+    }
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Fills {
     #[serde(rename = "FillStyle", default)]
     pub fill_styles: Vec<FillStyle>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct FillStyle {
     pub index: Option<u64>,
 
@@ -109,33 +110,44 @@ pub struct FillStyle {
     pub solid_color: Option<SolidColor>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct SolidColor {
-    pub color: Option<String>,
+    /// Colors without a color are black.
+    #[serde(default, deserialize_with = "deserialize_rgb")]
+    pub color: (u8, u8, u8),
 }
 
 impl SolidColor {
-    pub fn get_rgb(&self) -> Option<(u8, u8, u8)> {
-        let color_str = match self.color.as_ref() {
-            Some(color_str) => color_str,
-            None => return Some((0, 0, 0)), // Null is black apparently
-        };
+    pub fn get_rgb(&self) -> (u8, u8, u8) {
+        self.color
+    }
+}
 
-        let r = u8::from_str_radix(&color_str.get(1..3)?, 16).ok()?;
-        let g = u8::from_str_radix(&color_str.get(3..5)?, 16).ok()?;
-        let b = u8::from_str_radix(&color_str.get(5..7)?, 16).ok()?;
+/// Parse a color like `#RRGGBB`.
+pub fn parse_rgb(color_str: &str) -> Option<(u8, u8, u8)> {
+    let r = u8::from_str_radix(color_str.get(1..3)?, 16).ok()?;
+    let g = u8::from_str_radix(color_str.get(3..5)?, 16).ok()?;
+    let b = u8::from_str_radix(color_str.get(5..7)?, 16).ok()?;
 
-        Some((r, g, b))
-    }
+    Some((r, g, b))
+}
+
+/// Deserialize a color like `#RRGGBB`.
+pub(crate) fn deserialize_rgb<'de, D>(deserializer: D) -> Result<(u8, u8, u8), D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let color = <String as serde::Deserialize>::deserialize(deserializer)?;
+    parse_rgb(&color).ok_or_else(|| serde::de::Error::custom(format!("invalid color '{}'", color)))
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Strokes {
     #[serde(rename = "StrokeStyle", default)]
     pub stroke_styles: Vec<StrokeStyle>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct StrokeStyle {
     pub index: Option<u64>,
 
@@ -143,17 +155,27 @@ pub struct StrokeStyle {
     pub solid_stroke: SolidStroke,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct SolidStroke {
+    /// The width of the stroke, in pixels.
+    pub weight: Option<f64>,
+
     pub fill: Fill,
 }
 
-#[derive(Debug, serde::Deserialize)]
+impl SolidStroke {
+    /// Get the width of the stroke, in pixels. Strokes without a weight are 1 pixel wide.
+    pub fn get_weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Fill {
     pub solid_color: Option<SolidColor>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Edges {
     #[serde(rename = "Edge", default)]
     pub edges: Vec<Edge>,
@@ -6,7 +6,7 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Edge {
     #[serde(rename = "fillStyle1")]
     pub fill_style_1: Option<u64>,
@@ -47,7 +47,7 @@ pub enum FromStrError {
     ParseInt(#[from] std::num::ParseIntError),
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 #[serde(try_from = "String")]
 pub struct EdgeDefinition {
     pub commands: Vec<EdgeDefinitionCommand>,
@@ -213,7 +213,7 @@ impl<'a> EdgeDefinitionLexer<'a> {
         };
 
         if start_char == '#' {
-            return Ok(self.read_fixed_point()?);
+            return self.read_fixed_point();
         }
 
         let mut modifier = 1.0;
@@ -274,7 +274,7 @@ impl<'a> EdgeDefinitionLexer<'a> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EdgeDefinitionCommand {
     MoveTo(f64, f64),
     LineTo(f64, f64),
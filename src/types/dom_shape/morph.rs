@@ -0,0 +1,655 @@
+use crate::types::{
+    dom_shape::{
+        edge::EdgeDefinition,
+        Edge,
+        EdgeDefinitionCommand,
+        Edges,
+        FillStyle,
+        Fills,
+        SelectionMask,
+        SolidColor,
+        Strokes,
+    },
+    lerp,
+    lerp_rgb,
+    matrix::TWIPS_PER_PIXEL,
+    DomShape,
+    Matrix,
+    Point,
+};
+
+/// The furthest a shape hint can be from the edges of a shape, in twips.
+const HINT_DISTANCE: f64 = 2.0 * TWIPS_PER_PIXEL;
+
+/// Points closer than this, in twips, are considered equal.
+const EPSILON: f64 = 0.5;
+
+/// The `<morphShape>` element of a keyframe starting a shape tween.
+#[derive(Debug, serde::Deserialize)]
+pub struct MorphShape {
+    #[serde(rename = "morphHintsList", alias = "MorphHintsList")]
+    pub morph_hints_list: Option<MorphHintsList>,
+}
+
+impl MorphShape {
+    pub fn get_hints(&self) -> &[MorphHint] {
+        self.morph_hints_list
+            .as_ref()
+            .map(|list| list.hints.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MorphHintsList {
+    #[serde(rename = "MorphHint", default)]
+    pub hints: Vec<MorphHint>,
+}
+
+/// A shape hint, pairing a point of the first shape of a shape tween with a point of the last shape.
+///
+/// Points are stored like `x, y`, in pixels.
+#[derive(Debug, serde::Deserialize)]
+pub struct MorphHint {
+    pub name: Option<String>,
+
+    #[serde(rename = "startPoint")]
+    pub start_point: String,
+
+    #[serde(rename = "endPoint")]
+    pub end_point: String,
+}
+
+impl MorphHint {
+    /// Get the points of this hint, in pixels.
+    pub fn get_points(&self) -> Option<(Point, Point)> {
+        Some((
+            parse_point(&self.start_point)?,
+            parse_point(&self.end_point)?,
+        ))
+    }
+}
+
+/// Parse a point like `x, y`.
+fn parse_point(point: &str) -> Option<Point> {
+    let mut iter = point.split(',').map(|value| value.trim().parse::<f64>());
+    let x = iter.next()?.ok()?;
+    let y = iter.next()?.ok()?;
+
+    Some(Point { x, y })
+}
+
+/// Interpolate between two shapes, at `t` from 0 to 1.
+///
+/// Edges are paired in order, and each pair of edges is resampled to the same number of curves.
+/// Hints, in pixels, pin points of the first shape to points of the last shape.
+/// Edges without a pair grow from, or shrink to, their center.
+///
+/// Matrices are applied to the edges, so the returned shape has no matrix.
+pub fn morph_shape(start: &DomShape, end: &DomShape, hints: &[(Point, Point)], t: f64) -> DomShape {
+    let hints: Vec<_> = hints
+        .iter()
+        .map(|(start, end)| {
+            (
+                (start.x * TWIPS_PER_PIXEL, start.y * TWIPS_PER_PIXEL),
+                (end.x * TWIPS_PER_PIXEL, end.y * TWIPS_PER_PIXEL),
+            )
+        })
+        .collect();
+
+    let start_matrix = start.get_matrix();
+    let end_matrix = end.get_matrix();
+    let start_edges = start.get_edges();
+    let end_edges = end.get_edges();
+
+    let mut edges = Vec::with_capacity(start_edges.len().max(end_edges.len()));
+    for i in 0..start_edges.len().max(end_edges.len()) {
+        let start_edge = start_edges
+            .get(i)
+            .map(|edge| (edge, Contour::from_edge(edge, &start_matrix)));
+        let end_edge = end_edges
+            .get(i)
+            .map(|edge| (edge, Contour::from_edge(edge, &end_matrix)));
+
+        let (edge, contour) = match (start_edge, end_edge) {
+            (Some((edge, start)), Some((_, end))) => (edge, start.morph(&end, &hints, t)),
+            (Some((edge, start)), None) => (edge, start.morph(&start.collapse(), &[], t)),
+            (None, Some((edge, end))) => (edge, end.collapse().morph(&end, &[], t)),
+            (None, None) => continue,
+        };
+        edges.push(contour.to_edge(edge));
+    }
+
+    DomShape {
+        selected: None,
+        is_floating: start.is_floating,
+        matrices: None,
+        fills: Some(Fills {
+            fill_styles: morph_styles(
+                start
+                    .fills
+                    .iter()
+                    .flat_map(|fills| fills.fill_styles.iter()),
+                end.fills.iter().flat_map(|fills| fills.fill_styles.iter()),
+                |fill_style| fill_style.index,
+                |start, end| FillStyle {
+                    index: start.index,
+                    solid_color: morph_color(
+                        start.solid_color.as_ref(),
+                        end.solid_color.as_ref(),
+                        t,
+                    ),
+                },
+            ),
+        }),
+        strokes: Some(Strokes {
+            stroke_styles: morph_styles(
+                start
+                    .strokes
+                    .iter()
+                    .flat_map(|strokes| strokes.stroke_styles.iter()),
+                end.strokes
+                    .iter()
+                    .flat_map(|strokes| strokes.stroke_styles.iter()),
+                |stroke_style| stroke_style.index,
+                |start, end| {
+                    let mut ret = start.clone();
+                    ret.solid_stroke.fill.solid_color = morph_color(
+                        start.solid_stroke.fill.solid_color.as_ref(),
+                        end.solid_stroke.fill.solid_color.as_ref(),
+                        t,
+                    );
+                    ret
+                },
+            ),
+        }),
+        edges: Edges { edges },
+    }
+}
+
+/// Interpolate between the styles of two shapes, pairing them by index.
+///
+/// Styles only used by one of the shapes are kept as they are.
+fn morph_styles<'a, T: Clone + 'a>(
+    start: impl Iterator<Item = &'a T>,
+    end: impl Iterator<Item = &'a T> + Clone,
+    get_index: impl Fn(&T) -> Option<u64>,
+    morph: impl Fn(&T, &T) -> T,
+) -> Vec<T> {
+    let mut ret: Vec<T> = start
+        .map(|start| {
+            let index = get_index(start);
+            match end.clone().find(|end| get_index(end) == index) {
+                Some(end) => morph(start, end),
+                None => start.clone(),
+            }
+        })
+        .collect();
+
+    for style in end {
+        let index = get_index(style);
+        if !ret.iter().any(|style| get_index(style) == index) {
+            ret.push(style.clone());
+        }
+    }
+
+    ret
+}
+
+fn morph_color(start: Option<&SolidColor>, end: Option<&SolidColor>, t: f64) -> Option<SolidColor> {
+    match start.zip(end) {
+        Some((start, end)) => Some(SolidColor {
+            color: lerp_rgb(start.color, end.color, t),
+        }),
+        None => start.cloned(),
+    }
+}
+
+type Vec2 = (f64, f64);
+
+/// A quadratic curve, continuing from the end of the previous one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    control: Vec2,
+    end: Vec2,
+}
+
+/// The path of an edge, as a start point followed by quadratic curves, in twips.
+#[derive(Debug, Clone, PartialEq)]
+struct Contour {
+    start: Vec2,
+    segments: Vec<Segment>,
+    selection_mask: Option<SelectionMask>,
+}
+
+impl Contour {
+    fn from_edge(edge: &Edge, matrix: &Matrix) -> Self {
+        let transform = matrix.to_twips_transform();
+        let map = |x: f64, y: f64| {
+            let point = transform.transform_point(euclid::point2(x, y));
+            (point.x, point.y)
+        };
+
+        let mut ret = Self {
+            start: (0.0, 0.0),
+            segments: Vec::new(),
+            selection_mask: None,
+        };
+        let mut started = false;
+        for cmd in edge
+            .get_edge_definition_commands()
+            .iter()
+            .flat_map(|cmds| cmds.iter())
+        {
+            let point = match *cmd {
+                EdgeDefinitionCommand::MoveTo(x, y) if !started => {
+                    ret.start = map(x, y);
+                    started = true;
+                    continue;
+                }
+                // Edges are drawn as a single path, so moves are lines.
+                EdgeDefinitionCommand::MoveTo(x, y) | EdgeDefinitionCommand::LineTo(x, y) => {
+                    let end = map(x, y);
+                    if distance(ret.get_end(), end) < EPSILON {
+                        continue;
+                    }
+                    Segment {
+                        control: midpoint(ret.get_end(), end),
+                        end,
+                    }
+                }
+                EdgeDefinitionCommand::CurveTo(cx, cy, ex, ey) => Segment {
+                    control: map(cx, cy),
+                    end: map(ex, ey),
+                },
+                EdgeDefinitionCommand::Selection(selection_mask) => {
+                    ret.selection_mask.get_or_insert(selection_mask);
+                    continue;
+                }
+            };
+            ret.segments.push(point);
+        }
+
+        ret
+    }
+
+    /// Make an edge with the styles of `edge`, drawing this contour.
+    fn to_edge(&self, edge: &Edge) -> Edge {
+        let mut commands = Vec::with_capacity(self.segments.len() + 2);
+        commands.push(EdgeDefinitionCommand::MoveTo(self.start.0, self.start.1));
+        if let Some(selection_mask) = self.selection_mask {
+            commands.push(EdgeDefinitionCommand::Selection(selection_mask));
+        }
+        commands.extend(self.segments.iter().map(|segment| {
+            EdgeDefinitionCommand::CurveTo(
+                segment.control.0,
+                segment.control.1,
+                segment.end.0,
+                segment.end.1,
+            )
+        }));
+
+        Edge {
+            fill_style_1: edge.fill_style_1,
+            stroke_style: edge.stroke_style,
+            edges: Some(EdgeDefinition { commands }),
+        }
+    }
+
+    fn get_end(&self) -> Vec2 {
+        self.segments
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.start)
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.segments.is_empty() && distance(self.start, self.get_end()) < EPSILON
+    }
+
+    /// Get the start point of a segment, which is the end of the one before it.
+    fn get_vertex(&self, index: usize) -> Vec2 {
+        match index {
+            0 => self.start,
+            index => self.segments[index - 1].end,
+        }
+    }
+
+    /// Get the signed area of this contour, using the vertices of its segments.
+    fn get_area(&self) -> f64 {
+        let mut ret = 0.0;
+        let mut previous = self.start;
+        for segment in self.segments.iter() {
+            ret += previous.0 * segment.end.1 - segment.end.0 * previous.1;
+            previous = segment.end;
+        }
+
+        ret / 2.0
+    }
+
+    /// Get a contour with every point at the center of this contour.
+    fn collapse(&self) -> Self {
+        let count = (self.segments.len() + 1) as f64;
+        let (x, y) = self.segments.iter().fold(self.start, |(x, y), segment| {
+            (x + segment.end.0, y + segment.end.1)
+        });
+        let center = (x / count, y / count);
+
+        Self {
+            start: center,
+            segments: vec![
+                Segment {
+                    control: center,
+                    end: center,
+                };
+                self.segments.len()
+            ],
+            selection_mask: self.selection_mask,
+        }
+    }
+
+    fn reverse(&self) -> Self {
+        let mut segments = Vec::with_capacity(self.segments.len());
+        for (i, segment) in self.segments.iter().enumerate().rev() {
+            segments.push(Segment {
+                control: segment.control,
+                end: self.get_vertex(i),
+            });
+        }
+
+        Self {
+            start: self.get_end(),
+            segments,
+            selection_mask: self.selection_mask,
+        }
+    }
+
+    /// Start a closed contour at one of its vertices.
+    fn rotate(&self, index: usize) -> Self {
+        let mut segments = self.segments.clone();
+        segments.rotate_left(index);
+
+        Self {
+            start: self.get_vertex(index),
+            segments,
+            selection_mask: self.selection_mask,
+        }
+    }
+
+    /// Get the vertex closest to a point.
+    fn find_closest_vertex(&self, point: Vec2) -> usize {
+        (0..self.segments.len().max(1))
+            .min_by(|a, b| {
+                let a = distance(self.get_vertex(*a), point);
+                let b = distance(self.get_vertex(*b), point);
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Insert a vertex at the point of this contour closest to `point`, and return its index.
+    ///
+    /// Returns `None` if the contour is further than [`HINT_DISTANCE`] from the point.
+    fn insert_vertex(&mut self, point: Vec2) -> Option<usize> {
+        const STEPS: usize = 16;
+
+        let mut best: Option<(f64, usize, f64)> = None;
+        for i in 0..self.segments.len() {
+            let start = self.get_vertex(i);
+            let segment = self.segments[i];
+            for step in 0..=STEPS {
+                let t = step as f64 / STEPS as f64;
+                let d = distance(quad_point(start, segment, t), point);
+                if best.is_none_or(|(best, _, _)| d < best) {
+                    best = Some((d, i, t));
+                }
+            }
+        }
+
+        let (d, index, t) = best?;
+        if d > HINT_DISTANCE {
+            return None;
+        }
+        if t <= 0.0 {
+            return Some(index);
+        }
+        if t >= 1.0 {
+            return Some(index + 1);
+        }
+
+        self.split(index, t);
+        Some(index + 1)
+    }
+
+    /// Split a segment in two at `t`.
+    fn split(&mut self, index: usize, t: f64) {
+        let start = self.get_vertex(index);
+        let segment = self.segments[index];
+        let first_control = lerp_point(start, segment.control, t);
+        let second_control = lerp_point(segment.control, segment.end, t);
+        let middle = lerp_point(first_control, second_control, t);
+
+        self.segments[index] = Segment {
+            control: first_control,
+            end: middle,
+        };
+        self.segments.insert(
+            index + 1,
+            Segment {
+                control: second_control,
+                end: segment.end,
+            },
+        );
+    }
+
+    /// Split the longest segments of a range until it has `count` segments.
+    fn resample(&mut self, range: std::ops::Range<usize>, count: usize) {
+        let mut end = range.end;
+        while end - range.start < count {
+            let longest = (range.start..end).max_by(|a, b| {
+                let a = self.get_length(*a);
+                let b = self.get_length(*b);
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            match longest {
+                Some(index) => self.split(index, 0.5),
+                None => {
+                    // The range is empty, so grow it from its start point.
+                    let point = self.get_vertex(range.start);
+                    self.segments.insert(
+                        range.start,
+                        Segment {
+                            control: point,
+                            end: point,
+                        },
+                    );
+                }
+            }
+            end += 1;
+        }
+    }
+
+    /// Approximate the length of a segment.
+    fn get_length(&self, index: usize) -> f64 {
+        let start = self.get_vertex(index);
+        let segment = self.segments[index];
+        distance(start, segment.control) + distance(segment.control, segment.end)
+    }
+
+    /// Interpolate between this contour, at `t` = 0, and `other`, at `t` = 1.
+    fn morph(&self, other: &Self, hints: &[(Vec2, Vec2)], t: f64) -> Self {
+        let (start, end) = self.align(other, hints);
+
+        let mut ret = Self {
+            start: lerp_point(start.start, end.start, t),
+            segments: Vec::with_capacity(start.segments.len()),
+            selection_mask: self.selection_mask,
+        };
+        for (start, end) in start.segments.iter().zip(end.segments.iter()) {
+            ret.segments.push(Segment {
+                control: lerp_point(start.control, end.control, t),
+                end: lerp_point(start.end, end.end, t),
+            });
+        }
+
+        ret
+    }
+
+    /// Get copies of this contour and `other` with matching segments.
+    ///
+    /// Both contours start at the same hint, or at their closest vertices,
+    /// and every piece between two hints has the same number of segments in both contours.
+    fn align(&self, other: &Self, hints: &[(Vec2, Vec2)]) -> (Self, Self) {
+        let mut start = self.clone();
+        let mut end = other.clone();
+        let closed = start.is_closed() && end.is_closed();
+        if closed && (start.get_area() > 0.0) != (end.get_area() > 0.0) {
+            end = end.reverse();
+        }
+
+        // Pin the hints that are on both contours.
+        let mut anchors = Vec::new();
+        for (start_point, end_point) in hints.iter() {
+            let mut new_start = start.clone();
+            let mut new_end = end.clone();
+            let anchor = new_start
+                .insert_vertex(*start_point)
+                .zip(new_end.insert_vertex(*end_point));
+            if let Some(anchor) = anchor {
+                // Inserting a vertex shifts the anchors after it.
+                for (start_index, end_index) in anchors.iter_mut() {
+                    if new_start.segments.len() > start.segments.len() && *start_index >= anchor.0 {
+                        *start_index += 1;
+                    }
+                    if new_end.segments.len() > end.segments.len() && *end_index >= anchor.1 {
+                        *end_index += 1;
+                    }
+                }
+                start = new_start;
+                end = new_end;
+                anchors.push(anchor);
+            }
+        }
+
+        if closed {
+            let (start_index, end_index) = anchors
+                .first()
+                .copied()
+                .unwrap_or_else(|| (0, end.find_closest_vertex(start.start)));
+            let start_len = start.segments.len();
+            let end_len = end.segments.len();
+            start = start.rotate(start_index % start_len.max(1));
+            end = end.rotate(end_index % end_len.max(1));
+            for (start_anchor, end_anchor) in anchors.iter_mut() {
+                *start_anchor = (*start_anchor + start_len - start_index) % start_len.max(1);
+                *end_anchor = (*end_anchor + end_len - end_index) % end_len.max(1);
+            }
+        }
+
+        // Anchors must be in the same order along both contours.
+        anchors.sort_unstable();
+        anchors.dedup();
+        let mut last_end_anchor = 0;
+        anchors.retain(|(_, end_anchor)| {
+            let keep = *end_anchor >= last_end_anchor;
+            if keep {
+                last_end_anchor = *end_anchor;
+            }
+            keep
+        });
+        anchors.push((start.segments.len(), end.segments.len()));
+
+        // Resample the pieces between anchors, from last to first so the anchors stay valid.
+        let mut pieces = Vec::with_capacity(anchors.len());
+        let mut previous = (0, 0);
+        for anchor in anchors.iter() {
+            pieces.push((previous.0..anchor.0, previous.1..anchor.1));
+            previous = *anchor;
+        }
+        for (start_range, end_range) in pieces.into_iter().rev() {
+            if start_range.is_empty() && end_range.is_empty() {
+                continue;
+            }
+            let count = start_range.len().max(end_range.len()).max(1);
+            start.resample(start_range, count);
+            end.resample(end_range, count);
+        }
+
+        (start, end)
+    }
+}
+
+fn lerp_point(start: Vec2, end: Vec2, t: f64) -> Vec2 {
+    (lerp(start.0, end.0, t), lerp(start.1, end.1, t))
+}
+
+fn midpoint(a: Vec2, b: Vec2) -> Vec2 {
+    lerp_point(a, b, 0.5)
+}
+
+fn distance(a: Vec2, b: Vec2) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn quad_point(start: Vec2, segment: Segment, t: f64) -> Vec2 {
+    lerp_point(
+        lerp_point(start, segment.control, t),
+        lerp_point(segment.control, segment.end, t),
+        t,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rect(color: &str, width: u32) -> DomShape {
+        quick_xml::de::from_str(&format!(
+            r#"<DOMShape><fills><FillStyle index="1"><SolidColor color="{}"/></FillStyle></fills><edges><Edge fillStyle1="1" edges="!0 0S2|{w} 0|{w} 400|0 400|0 0"/></edges></DOMShape>"#,
+            color,
+            w = width
+        ))
+        .unwrap()
+    }
+
+    fn get_start(shape: &DomShape) -> (f64, f64) {
+        match shape.get_edges()[0].get_edge_definition_commands().unwrap()[0] {
+            EdgeDefinitionCommand::MoveTo(x, y) => (x, y),
+            _ => panic!("edges should start with a move"),
+        }
+    }
+
+    #[test]
+    fn morph_geometry_and_color() {
+        let start = rect("#FF0000", 400);
+        let end = rect("#0000FF", 800);
+
+        let shape = morph_shape(&start, &end, &[], 0.5);
+        let bounds = shape.calc_bounding_box().unwrap();
+        assert_eq!((bounds.min.x, bounds.max.x), (0.0, 600.0));
+        assert_eq!((bounds.min.y, bounds.max.y), (0.0, 400.0));
+        assert_eq!(
+            shape
+                .get_fill_style(1)
+                .unwrap()
+                .solid_color
+                .as_ref()
+                .unwrap()
+                .color,
+            (0x80, 0, 0x80)
+        );
+    }
+
+    #[test]
+    fn hints_pin_points() {
+        let start = rect("#FF0000", 400);
+        let end = rect("#FF0000", 400);
+        assert_eq!(get_start(&morph_shape(&start, &end, &[], 0.5)), (0.0, 0.0));
+
+        let hint: MorphHint =
+            quick_xml::de::from_str(r#"<MorphHint name="a" startPoint="0, 0" endPoint="20, 0"/>"#)
+                .unwrap();
+        let shape = morph_shape(&start, &end, &[hint.get_points().unwrap()], 0.5);
+        assert_eq!(get_start(&shape), (200.0, 0.0));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::types::{
+    dom_shape::EdgeDefinitionCommand,
+    matrix::TWIPS_PER_PIXEL,
+    DomShape,
+    Point,
+};
+
+/// The number of lines a curve is split into.
+const CURVE_STEPS: usize = 16;
+
+/// Points closer than this, in pixels, are considered equal.
+const EPSILON: f64 = 0.05;
+
+type Vec2 = (f64, f64);
+
+/// The path drawn by the strokes of a motion guide, as a polyline in pixels.
+///
+/// Positions along the path are distances from its start, in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuidePath {
+    points: Vec<Vec2>,
+
+    /// The distance of each point from the start of the path.
+    distances: Vec<f64>,
+}
+
+impl GuidePath {
+    /// Make a path from the stroked edges of shapes.
+    ///
+    /// Edges are joined where they meet, starting from the first edge.
+    /// Edges that do not connect to the path are ignored.
+    ///
+    /// Returns `None` if the shapes have no strokes.
+    pub fn from_shapes<'a>(shapes: impl IntoIterator<Item = &'a DomShape>) -> Option<Self> {
+        let mut pieces = Vec::new();
+        for shape in shapes {
+            let matrix = shape.get_matrix();
+            let map =
+                |x: f64, y: f64| matrix.transform_point(x / TWIPS_PER_PIXEL, y / TWIPS_PER_PIXEL);
+
+            for edge in shape
+                .get_edges()
+                .iter()
+                .filter(|edge| edge.stroke_style.unwrap_or(0) != 0)
+            {
+                let mut piece: Vec<Vec2> = Vec::new();
+                for cmd in edge
+                    .get_edge_definition_commands()
+                    .iter()
+                    .flat_map(|cmds| cmds.iter())
+                {
+                    match *cmd {
+                        EdgeDefinitionCommand::MoveTo(x, y) => {
+                            let point = map(x, y);
+                            if piece
+                                .last()
+                                .is_some_and(|last| distance(*last, point) > EPSILON)
+                            {
+                                pieces.push(std::mem::take(&mut piece));
+                            }
+                            if piece.is_empty() {
+                                piece.push(point);
+                            }
+                        }
+                        EdgeDefinitionCommand::LineTo(x, y) => {
+                            piece.push(map(x, y));
+                        }
+                        EdgeDefinitionCommand::CurveTo(cx, cy, ex, ey) => {
+                            let start = match piece.last() {
+                                Some(start) => *start,
+                                None => continue,
+                            };
+                            let (control, end) = (map(cx, cy), map(ex, ey));
+                            piece.extend((1..=CURVE_STEPS).map(|step| {
+                                quad_point(start, control, end, step as f64 / CURVE_STEPS as f64)
+                            }));
+                        }
+                        EdgeDefinitionCommand::Selection(_) => {}
+                    }
+                }
+                pieces.push(piece);
+            }
+        }
+        pieces.retain(|piece| piece.len() > 1);
+
+        let mut points = pieces.first()?.clone();
+        pieces.remove(0);
+
+        // Join the remaining pieces to either end of the path.
+        loop {
+            let (start, end) = (points[0], points[points.len() - 1]);
+            let position = pieces.iter().position(|piece| {
+                let (first, last) = (piece[0], piece[piece.len() - 1]);
+                [first, last].iter().any(|point| {
+                    distance(*point, start) < EPSILON || distance(*point, end) < EPSILON
+                })
+            });
+            let mut piece = match position {
+                Some(position) => pieces.remove(position),
+                None => break,
+            };
+
+            if distance(piece[0], end) < EPSILON {
+                points.extend_from_slice(&piece[1..]);
+            } else if distance(piece[piece.len() - 1], end) < EPSILON {
+                piece.reverse();
+                points.extend_from_slice(&piece[1..]);
+            } else if distance(piece[piece.len() - 1], start) < EPSILON {
+                piece.pop();
+                piece.extend_from_slice(&points);
+                points = piece;
+            } else {
+                piece.reverse();
+                piece.pop();
+                piece.extend_from_slice(&points);
+                points = piece;
+            }
+        }
+        points.dedup_by(|a, b| distance(*a, *b) < EPSILON);
+
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                total += distance(points[i - 1], *point);
+            }
+            distances.push(total);
+        }
+
+        Some(Self { points, distances })
+    }
+
+    /// Get the length of the path, in pixels.
+    pub fn get_length(&self) -> f64 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Get the position along the path of the point of the path closest to a point.
+    pub fn get_closest_position(&self, point: &Point) -> f64 {
+        let point = (point.x, point.y);
+        let mut best = (f64::INFINITY, 0.0);
+        for (i, segment) in self.points.windows(2).enumerate() {
+            let (start, end) = (segment[0], segment[1]);
+            let length = self.distances[i + 1] - self.distances[i];
+            let t = if length > 0.0 {
+                (((point.0 - start.0) * (end.0 - start.0)
+                    + (point.1 - start.1) * (end.1 - start.1))
+                    / (length * length))
+                    .clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let closest = (
+                start.0 + (end.0 - start.0) * t,
+                start.1 + (end.1 - start.1) * t,
+            );
+            let closest_distance = distance(closest, point);
+            if closest_distance < best.0 {
+                best = (closest_distance, self.distances[i] + length * t);
+            }
+        }
+
+        best.1
+    }
+
+    /// Get the point at a position along the path, and the direction of the path there in radians.
+    ///
+    /// Positions are clamped to the path.
+    pub fn get_point(&self, position: f64) -> (Point, f64) {
+        if self.points.len() < 2 {
+            let (x, y) = self.points.first().copied().unwrap_or_default();
+            return (Point { x, y }, 0.0);
+        }
+
+        let position = position.clamp(0.0, self.get_length());
+        let i = self
+            .distances
+            .partition_point(|distance| *distance <= position)
+            .clamp(1, self.points.len() - 1);
+        let (start, end) = (self.points[i - 1], self.points[i]);
+        let length = self.distances[i] - self.distances[i - 1];
+        let t = if length > 0.0 {
+            (position - self.distances[i - 1]) / length
+        } else {
+            0.0
+        };
+
+        (
+            Point {
+                x: start.0 + (end.0 - start.0) * t,
+                y: start.1 + (end.1 - start.1) * t,
+            },
+            (end.1 - start.1).atan2(end.0 - start.0),
+        )
+    }
+}
+
+fn distance(a: Vec2, b: Vec2) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+fn quad_point(start: Vec2, control: Vec2, end: Vec2, t: f64) -> Vec2 {
+    let u = 1.0 - t;
+    (
+        u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+        u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn join_stroked_edges() {
+        // Two lines meeting at (100, 0), with the second drawn backwards, and a fill edge.
+        let shape: DomShape = quick_xml::de::from_str(
+            r##"<DOMShape>
+                <edges>
+                    <Edge strokeStyle="1" edges="!0 0|2000 0"/>
+                    <Edge strokeStyle="1" edges="!2000 2000|2000 0"/>
+                    <Edge fillStyle1="1" edges="!0 0|4000 4000"/>
+                </edges>
+            </DOMShape>"##,
+        )
+        .unwrap();
+        let path = GuidePath::from_shapes(std::iter::once(&shape)).unwrap();
+        assert_eq!(path.get_length(), 200.0);
+
+        let (point, angle) = path.get_point(150.0);
+        assert_eq!((point.x, point.y), (100.0, 50.0));
+        assert!((angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+
+        assert_eq!(
+            path.get_closest_position(&Point { x: 40.0, y: -10.0 }),
+            40.0
+        );
+        assert_eq!(
+            path.get_closest_position(&Point { x: 150.0, y: 300.0 }),
+            200.0
+        );
+    }
+}
@@ -1,11 +1,32 @@
-#[cfg(feature = "render-raqote")]
-use crate::types::dom_shape::{
-    EdgeDefinitionCommand,
-    SelectionMask,
+use crate::{
+    bitmap::BitmapError,
+    sampler::SampleError,
+    text::FontError,
+    types::{
+        dom_symbol_instance::SymbolType,
+        dom_timeline::{
+            FrameLabel,
+            LayerPolicy,
+        },
+        scale_grid::{
+            ScaleGrid,
+            ScaleGridMapping,
+        },
+        DomLayer,
+        DomTimeline,
+        Matrix,
+    },
+    Fla,
 };
-use crate::types::{
-    DomLayer,
-    DomTimeline,
+#[cfg(feature = "render-raqote")]
+use crate::{
+    player::Player,
+    render::{
+        raqote::RaqoteRenderer,
+        RenderOptions,
+        TimelineRenderer,
+        View,
+    },
 };
 
 #[derive(Debug, serde::Deserialize)]
@@ -24,25 +45,81 @@ pub struct DomSymbol {
     #[serde(rename = "lastModified")]
     pub last_modified: String,
 
+    #[serde(rename = "symbolType")]
+    pub symbol_type: Option<SymbolType>,
+
+    #[serde(rename = "scaleGridLeft")]
+    pub scale_grid_left: Option<f64>,
+
+    #[serde(rename = "scaleGridRight")]
+    pub scale_grid_right: Option<f64>,
+
+    #[serde(rename = "scaleGridTop")]
+    pub scale_grid_top: Option<f64>,
+
+    #[serde(rename = "scaleGridBottom")]
+    pub scale_grid_bottom: Option<f64>,
+
     pub timeline: Timeline,
 }
 
 impl DomSymbol {
     pub fn get_layers(&self) -> &[DomLayer] {
-        &self.timeline.dom_timeline.get_layers()
+        self.timeline.dom_timeline.get_layers()
     }
 
     pub fn get_layer(&self, index: usize) -> Option<&DomLayer> {
         self.get_layers().get(index)
     }
 
-    pub fn calc_bounding_box(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+    pub fn calc_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        self.calc_nested_bounding_box(fla, layer_policy, &[], false)
+    }
+
+    /// Calculate the bounding box of everything this symbol draws,
+    /// including the area drawn outside of its content by filters, like blurs and drop shadows.
+    pub fn calc_render_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        self.calc_nested_bounding_box(fla, layer_policy, &[], true)
+    }
+
+    /// Calculate the bounding box of this symbol, nested in the symbols named by `ancestors`.
+    ///
+    /// A symbol nested in itself does not have a bounding box, so instances that would nest forever are skipped.
+    /// If `include_filters` is set, the bounding box includes the area drawn by filters of symbol instances.
+    pub(crate) fn calc_nested_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        ancestors: &[&str],
+        include_filters: bool,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        if ancestors.contains(&self.name.as_str()) {
+            return None;
+        }
+        let mut ancestors = ancestors.to_vec();
+        ancestors.push(&self.name);
+
+        // Guides are not rendered, but motion guides bound the paths of the instances they guide.
+        let timeline = &self.timeline.dom_timeline;
         let mut ret = None;
         for bounding_box in self
             .get_layers()
             .iter()
-            .map(|l| l.calc_bounding_box())
-            .flatten()
+            .enumerate()
+            .filter(|(i, _)| {
+                timeline.should_render_layer(*i, layer_policy) || timeline.is_motion_guide(*i)
+            })
+            .filter_map(|(_, l)| {
+                l.calc_bounding_box(fla, layer_policy, &ancestors, include_filters)
+            })
         {
             let bb = ret.get_or_insert(bounding_box);
             bb.min.x = bb.min.x.min(bounding_box.min.x);
@@ -55,21 +132,118 @@ impl DomSymbol {
     }
 
     pub fn num_frames(&self) -> usize {
-        let mut num_frames = 0;
-        for layer in self.get_layers().iter() {
-            num_frames = num_frames.max(layer.num_frames());
+        self.timeline.dom_timeline.num_frames()
+    }
+
+    /// Get the labels, comments and anchors of this symbol's timeline, ordered by frame.
+    pub fn get_labels(&self) -> Vec<FrameLabel<'_>> {
+        self.timeline.dom_timeline.get_labels()
+    }
+
+    /// Get the frames of a label, 0-indexed.
+    ///
+    /// A label spans from its frame to the next label, or to the end of the timeline.
+    pub fn get_label_range(&self, name: &str) -> Option<std::ops::Range<usize>> {
+        self.timeline.dom_timeline.get_label_range(name)
+    }
+
+    /// Get the symbol type. Symbols without a symbol type are movie clips.
+    pub fn get_symbol_type(&self) -> SymbolType {
+        self.symbol_type.unwrap_or(SymbolType::MovieClip)
+    }
+
+    /// Get the 9-slice scaling grid, if this symbol has one.
+    pub fn get_scale_grid(&self) -> Option<ScaleGrid> {
+        Some(ScaleGrid {
+            left: self.scale_grid_left?,
+            right: self.scale_grid_right?,
+            top: self.scale_grid_top?,
+            bottom: self.scale_grid_bottom?,
+        })
+    }
+
+    /// Get the 9-slice mapping of an instance of this symbol with a matrix.
+    ///
+    /// Like the Flash Player, the grid is only used if the instance is scaled without being rotated, skewed or flipped.
+    pub fn get_scale_grid_mapping(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        matrix: &Matrix,
+    ) -> Option<ScaleGridMapping> {
+        let scale_grid = self.get_scale_grid()?;
+        if matrix.b != 0.0 || matrix.c != 0.0 || matrix.a <= 0.0 || matrix.d <= 0.0 {
+            return None;
         }
+        if matrix.a == 1.0 && matrix.d == 1.0 {
+            return None;
+        }
+
+        let bounds = fla.get_symbol_bounding_box(self, layer_policy)?;
+        Some(scale_grid.get_mapping(&bounds, matrix.a, matrix.d))
+    }
 
-        num_frames
+    pub fn is_button(&self) -> bool {
+        self.get_symbol_type() == SymbolType::Button
+    }
+
+    /// Get the frame holding a button state, 0-indexed.
+    ///
+    /// Returns `None` if this is not a button or the button does not have a frame for that state.
+    pub fn get_button_state_frame(&self, state: ButtonState) -> Option<usize> {
+        if !self.is_button() {
+            return None;
+        }
+
+        let frame_index = state.get_frame_index();
+        if frame_index >= self.num_frames() {
+            return None;
+        }
+
+        Some(frame_index)
+    }
+
+    /// Get the frame holding the hit area of a button, 0-indexed.
+    ///
+    /// Like the Flash Player, this falls back to the up state if the hit state is missing.
+    pub fn get_button_hit_frame(&self) -> Option<usize> {
+        self.get_button_state_frame(ButtonState::Hit)
+            .or_else(|| self.get_button_state_frame(ButtonState::Up))
+    }
+}
+
+/// The states of a button. Each state is a frame of the button's timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    Up,
+    Over,
+    Down,
+    Hit,
+}
+
+impl ButtonState {
+    /// Get the frame of a button's timeline holding this state, 0-indexed.
+    pub fn get_frame_index(self) -> usize {
+        match self {
+            Self::Up => 0,
+            Self::Over => 1,
+            Self::Down => 2,
+            Self::Hit => 3,
+        }
     }
 }
 
-#[cfg(feature = "render-raqote")]
 #[derive(Debug, thiserror::Error)]
 pub enum DomSymbolRenderError {
     #[error("Could not determine a bounding box")]
     NoBoundingBox,
 
+    #[error("Missing Button State {0:?}")]
+    MissingButtonState(ButtonState),
+
+    #[error("Missing Label '{0}'")]
+    MissingLabel(String),
+
     #[error("Missing FillStyle{0}")]
     MissingFillStyleIndex(usize),
 
@@ -85,8 +259,17 @@ pub enum DomSymbolRenderError {
     #[error("Missing Color")]
     MissingColor,
 
-    #[error("InvalidRbg")]
-    InvalidRbg,
+    #[error(transparent)]
+    Sample(#[from] SampleError),
+
+    #[error("Missing Bitmap Data '{0}'")]
+    MissingBitmapData(String),
+
+    #[error(transparent)]
+    Bitmap(#[from] BitmapError),
+
+    #[error(transparent)]
+    Font(#[from] FontError),
 
     #[error("unsupported: {0}")]
     Unsupported(&'static str),
@@ -94,173 +277,147 @@ pub enum DomSymbolRenderError {
 
 #[cfg(feature = "render-raqote")]
 impl DomSymbol {
-    /// Render a DomSymbol.
-    pub fn render_raqote(
-        &self,
+    /// Render a DomSymbol, resolving symbol instances from the given fla.
+    ///
+    /// One frame is rendered for every frame of the symbol's timeline.
+    pub fn render_raqote<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+    ) -> Result<Vec<raqote::DrawTarget>, DomSymbolRenderError> {
+        self.render_raqote_ticks(fla, options, scale, padding, self.num_frames())
+    }
+
+    /// Render a DomSymbol for a number of ticks, resolving symbol instances from the given fla.
+    ///
+    /// Movie clip instances are played independently of this symbol's timeline, like the Flash Player.
+    pub fn render_raqote_ticks<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
         scale: f64,
         padding: f64,
+        num_ticks: usize,
     ) -> Result<Vec<raqote::DrawTarget>, DomSymbolRenderError> {
-        let bounding_box = self
-            .calc_bounding_box()
-            .ok_or(DomSymbolRenderError::NoBoundingBox)?;
-        let draw_target_width = (bounding_box.width() * scale) as i32 + padding as i32;
-        let draw_target_height = (bounding_box.height() * scale) as i32 + padding as i32;
+        let view = View::fit_symbol(self, fla, &options.layer_policy, scale, padding)?;
+        let renderer = TimelineRenderer::new(fla, &view, options);
+        let mut frames = Vec::with_capacity(num_ticks);
+
+        let mut player = Player::new(fla, self, options.layer_policy)?;
+        for _ in 0..num_ticks {
+            let mut backend = RaqoteRenderer::new(view.width, view.height);
+            renderer.render_symbol(
+                &mut backend,
+                self,
+                player.get_frame_index(),
+                Some(player.get_playhead()),
+            )?;
+            frames.push(backend.finish());
+
+            player.tick()?;
+        }
 
-        let num_frames = self.num_frames();
-        let mut frames = Vec::with_capacity(num_frames);
+        Ok(frames)
+    }
 
-        let mut layers: Vec<_> = self
-            .get_layers()
-            .iter()
-            .map(|layer| layer.get_frames().iter().cycle())
-            .collect();
+    /// Render a single frame of a DomSymbol, 0-indexed.
+    ///
+    /// All frames share the size and origin of the symbol's bounding box.
+    pub fn render_raqote_frame<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+        frame_index: usize,
+    ) -> Result<raqote::DrawTarget, DomSymbolRenderError> {
+        let view = View::fit_symbol(self, fla, &options.layer_policy, scale, padding)?;
+        let renderer = TimelineRenderer::new(fla, &view, options);
 
-        let transform = raqote::Transform::create_translation(
-            (-bounding_box.min.x * scale) as f32 + (padding / 2.0) as f32,
-            (-bounding_box.min.y * scale) as f32 + (padding / 2.0) as f32,
-        )
-        .pre_scale(scale as f32, scale as f32);
-
-        let draw_options = raqote::DrawOptions::new();
-
-        for _ in 0..num_frames {
-            let mut target = raqote::DrawTarget::new(draw_target_width, draw_target_height);
-
-            for frame in layers
-                .iter_mut()
-                .map(|layer_frame_iter| layer_frame_iter.next().unwrap())
-            {
-                for shape in frame.get_shapes() {
-                    for edge in shape.get_edges().iter() {
-                        let mut pb = raqote::PathBuilder::new();
-                        let mut last_selection_mask = None;
-
-                        for cmd in edge
-                            .get_edge_definition_commands()
-                            .iter()
-                            .map(|cmds| cmds.iter())
-                            .flatten()
-                        {
-                            match cmd {
-                                EdgeDefinitionCommand::MoveTo(x, y) => {
-                                    let x = *x as f32;
-                                    let y = *y as f32;
-
-                                    if last_selection_mask.is_none() {
-                                        pb.move_to(x, y);
-                                    } else {
-                                        pb.line_to(x, y);
-                                    }
-                                }
-                                EdgeDefinitionCommand::LineTo(x, y) => {
-                                    let x = *x as f32;
-                                    let y = *y as f32;
-                                    pb.line_to(x, y);
-                                }
-                                EdgeDefinitionCommand::Selection(selection_mask) => {
-                                    if let Some(_last_selection_mask) = last_selection_mask {
-                                        // TODO: Write to target and instantiate new path builder.
-                                        return Err(DomSymbolRenderError::Unsupported(
-                                            "SelectionMask overwrite",
-                                        ));
-                                    } else {
-                                        last_selection_mask = Some(selection_mask);
-                                    }
-                                }
-                                EdgeDefinitionCommand::CurveTo(x, y, ex, ey) => {
-                                    let x = *x as f32;
-                                    let y = *y as f32;
-                                    let ex = *ex as f32;
-                                    let ey = *ey as f32;
-                                    pb.quad_to(x, y, ex, ey);
-                                }
-                            }
-                        }
-                        pb.close();
-
-                        if let Some(selection_mask) = last_selection_mask {
-                            let path = pb.finish().transform(&transform);
-
-                            if selection_mask.contains(SelectionMask::FILLSTYLE0) {
-                                return Err(DomSymbolRenderError::Unsupported("FILLSTYLE0"));
-                            }
-
-                            if selection_mask.contains(SelectionMask::FILLSTYLE1) {
-                                let fill_style_1_index = edge
-                                    .fill_style_1
-                                    .ok_or(DomSymbolRenderError::MissingFillStyleIndex(1))?;
-                                let fill_style_1 = shape.get_fill_style(fill_style_1_index).ok_or(
-                                    DomSymbolRenderError::MissingFillStyle(fill_style_1_index),
-                                )?;
-
-                                // Only support solid color for now
-                                let color = fill_style_1
-                                    .solid_color
-                                    .as_ref()
-                                    .ok_or(DomSymbolRenderError::MissingColor)?
-                                    .get_rgb()
-                                    .ok_or(DomSymbolRenderError::InvalidRbg)?;
-
-                                let color = raqote::SolidSource {
-                                    r: color.0,
-                                    g: color.1,
-                                    b: color.2,
-                                    a: 0xFF,
-                                };
-                                target.fill(&path, &raqote::Source::Solid(color), &draw_options);
-                            }
-
-                            if selection_mask.contains(SelectionMask::STROKE) {
-                                let stroke_style_index = edge
-                                    .stroke_style
-                                    .ok_or(DomSymbolRenderError::MissingStrokeStyleIndex)?;
-                                let stroke_style = shape
-                                    .get_stroke_style(stroke_style_index)
-                                    .ok_or(DomSymbolRenderError::MissingStrokeStyle(
-                                        stroke_style_index,
-                                    ))?;
-
-                                // Only support solid color for now
-                                let color = stroke_style
-                                    .solid_stroke
-                                    .fill
-                                    .solid_color
-                                    .as_ref()
-                                    .map(|solid_color| {
-                                        solid_color
-                                            .get_rgb()
-                                            .ok_or(DomSymbolRenderError::InvalidRbg)
-                                    })
-                                    .unwrap_or(Ok((0, 0, 0)))?;
-
-                                let color = raqote::SolidSource {
-                                    r: color.0,
-                                    g: color.1,
-                                    b: color.2,
-                                    a: 0xFF,
-                                };
-
-                                let mut stroke_style = raqote::StrokeStyle::default();
-                                stroke_style.cap = raqote::LineCap::Round;
-                                stroke_style.join = raqote::LineJoin::Round;
-                                stroke_style.width = 20.0 * scale as f32;
-
-                                target.stroke(
-                                    &path,
-                                    &raqote::Source::Solid(color),
-                                    &stroke_style,
-                                    &draw_options,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-
-            frames.push(target);
+        let mut backend = RaqoteRenderer::new(view.width, view.height);
+        renderer.render_symbol(&mut backend, self, frame_index, None)?;
+
+        Ok(backend.finish())
+    }
+
+    /// Render a range of frames of a DomSymbol, 0-indexed.
+    ///
+    /// All frames share the size and origin of the symbol's bounding box.
+    pub fn render_raqote_frames<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+        frames: std::ops::Range<usize>,
+    ) -> Result<Vec<raqote::DrawTarget>, DomSymbolRenderError> {
+        let view = View::fit_symbol(self, fla, &options.layer_policy, scale, padding)?;
+        let renderer = TimelineRenderer::new(fla, &view, options);
+
+        frames
+            .map(|frame_index| {
+                let mut backend = RaqoteRenderer::new(view.width, view.height);
+                renderer.render_symbol(&mut backend, self, frame_index, None)?;
+
+                Ok(backend.finish())
+            })
+            .collect()
+    }
+
+    /// Render the frames of a label, like `walk_start`.
+    pub fn render_raqote_label<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+        label: &str,
+    ) -> Result<Vec<raqote::DrawTarget>, DomSymbolRenderError> {
+        let frames = self
+            .get_label_range(label)
+            .ok_or_else(|| DomSymbolRenderError::MissingLabel(label.to_string()))?;
+
+        self.render_raqote_frames(fla, options, scale, padding, frames)
+    }
+
+    /// Render a state of a button.
+    pub fn render_raqote_button_state<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+        state: ButtonState,
+    ) -> Result<raqote::DrawTarget, DomSymbolRenderError> {
+        let frame_index = self
+            .get_button_state_frame(state)
+            .ok_or(DomSymbolRenderError::MissingButtonState(state))?;
+
+        self.render_raqote_frame(fla, options, scale, padding, frame_index)
+    }
+
+    /// Render the hit area of a button as a mask.
+    ///
+    /// Pixels inside the hit area are opaque white, and all other pixels are transparent.
+    pub fn render_raqote_hit_area<'a>(
+        &'a self,
+        fla: &'a Fla,
+        options: &'a RenderOptions,
+        scale: f64,
+        padding: f64,
+    ) -> Result<raqote::DrawTarget, DomSymbolRenderError> {
+        let frame_index = self
+            .get_button_hit_frame()
+            .ok_or(DomSymbolRenderError::MissingButtonState(ButtonState::Hit))?;
+
+        let mut target = self.render_raqote_frame(fla, options, scale, padding, frame_index)?;
+        for pixel in target.get_data_mut().iter_mut() {
+            *pixel = if *pixel >> 24 != 0 { 0xFF_FF_FF_FF } else { 0 };
         }
 
-        Ok(frames)
+        Ok(target)
     }
 }
 
@@ -269,3 +426,145 @@ pub struct Timeline {
     #[serde(rename = "DOMTimeline")]
     pub dom_timeline: DomTimeline,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{
+        frame,
+        layer,
+        make_fla,
+        square,
+        symbol,
+    };
+    #[cfg(feature = "render-raqote")]
+    use crate::{
+        test_util::{
+            dot,
+            instance,
+        },
+        types::matrix::TWIPS_PER_PIXEL,
+    };
+
+    /// A button with keyframes at the given frames, each holding the given elements.
+    fn button(name: &'static str, frames: &[(usize, &str)]) -> (&'static str, String) {
+        let frames: String = frames
+            .iter()
+            .map(|(index, elements)| frame(*index, "", elements))
+            .collect();
+        (
+            name,
+            symbol(name, r#"symbolType="button""#, &layer("", &frames)),
+        )
+    }
+
+    /// A symbol holding two instances of itself, which would nest forever.
+    fn make_loop_fla() -> Fla {
+        let elements = format!(
+            r#"{}<DOMSymbolInstance libraryItemName="Loop" symbolType="graphic"/><DOMSymbolInstance libraryItemName="Loop" symbolType="graphic"><matrix><Matrix tx="5"/></matrix></DOMSymbolInstance>"#,
+            square("#FF0000", 10)
+        );
+        make_fla(
+            &[(
+                "Loop",
+                symbol("Loop", "", &layer("", &frame(0, "", &elements))),
+            )],
+            "",
+            &[],
+        )
+    }
+
+    #[test]
+    fn bounding_box_skips_cycles() {
+        let fla = make_loop_fla();
+        let bounding_box = fla
+            .get_symbol("Loop")
+            .unwrap()
+            .calc_bounding_box(&fla, &LayerPolicy::default())
+            .unwrap();
+        assert_eq!(bounding_box.min, euclid::point2(0.0, 0.0));
+        assert_eq!(bounding_box.max, euclid::point2(200.0, 200.0));
+    }
+
+    #[cfg(feature = "render-raqote")]
+    #[test]
+    fn render_recursion_limit() {
+        let fla = make_loop_fla();
+        let result = fla.get_symbol("Loop").unwrap().render_raqote_frame(
+            &fla,
+            &RenderOptions::default(),
+            1.0,
+            0.0,
+            0,
+        );
+        assert!(matches!(
+            result,
+            Err(DomSymbolRenderError::Sample(SampleError::RecursionLimit(name))) if name == "Loop"
+        ));
+    }
+
+    #[test]
+    fn button_frames() {
+        let up = square("#FF0000", 10);
+        let fla = make_fla(
+            &[
+                (
+                    "Graphic",
+                    symbol("Graphic", "", &layer("", &frame(0, "", &up))),
+                ),
+                button("Up", &[(0, &up)]),
+                button("Full", &[(0, &up), (1, &up), (2, &up), (3, &up)]),
+            ],
+            "",
+            &[],
+        );
+
+        let graphic = fla.get_symbol("Graphic").unwrap();
+        assert_eq!(graphic.get_button_state_frame(ButtonState::Up), None);
+        assert_eq!(graphic.get_button_hit_frame(), None);
+
+        // Missing states are not shown, except the hit area, which falls back to the up state.
+        let up = fla.get_symbol("Up").unwrap();
+        assert_eq!(up.get_button_state_frame(ButtonState::Up), Some(0));
+        assert_eq!(up.get_button_state_frame(ButtonState::Over), None);
+        assert_eq!(up.get_button_state_frame(ButtonState::Down), None);
+        assert_eq!(up.get_button_hit_frame(), Some(0));
+
+        let full = fla.get_symbol("Full").unwrap();
+        assert_eq!(full.get_button_state_frame(ButtonState::Down), Some(2));
+        assert_eq!(full.get_button_hit_frame(), Some(3));
+    }
+
+    #[cfg(feature = "render-raqote")]
+    #[test]
+    fn render_button() {
+        let faint = instance(
+            "Dot",
+            "",
+            r#"<color><Color alphaMultiplier="0.1"/></color>"#,
+        ) + &instance(
+            "Dot",
+            "",
+            r#"<matrix><Matrix tx="20"/></matrix><color><Color alphaMultiplier="0.1"/></color>"#,
+        );
+        let fla = make_fla(&[dot(), button("Button", &[(0, &faint)])], "", &[]);
+        let button = fla.get_symbol("Button").unwrap();
+        let options = RenderOptions::default();
+        let scale = 1.0 / TWIPS_PER_PIXEL;
+
+        assert!(matches!(
+            button.render_raqote_button_state(&fla, &options, scale, 0.0, ButtonState::Over),
+            Err(DomSymbolRenderError::MissingButtonState(ButtonState::Over))
+        ));
+
+        // Any coverage is part of the hit area, which is drawn opaque white.
+        let target = button
+            .render_raqote_hit_area(&fla, &options, scale, 0.0)
+            .unwrap();
+        assert_eq!(target.width(), 30);
+        let data = target.get_data();
+        assert_eq!(data[5 * 30 + 5], 0xFFFFFFFF);
+        assert_eq!(data[5 * 30 + 15], 0);
+        assert_eq!(data[5 * 30 + 25], 0xFFFFFFFF);
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    types::{
+        dom_timeline::LayerPolicy,
+        matrix::TWIPS_PER_PIXEL,
+        ColorTransform,
+        Colors,
+        Filter,
+        Filters,
+        Matrices,
+        Matrices3D,
+        Matrix,
+        Matrix3D,
+        Point,
+    },
+    Fla,
+};
+
+/// An instance of a library symbol placed in a frame.
+#[derive(Debug, serde::Deserialize)]
+pub struct DomSymbolInstance {
+    #[serde(rename = "libraryItemName")]
+    pub library_item_name: String,
+
+    pub name: Option<String>,
+    pub selected: Option<bool>,
+
+    #[serde(rename = "symbolType")]
+    pub symbol_type: Option<SymbolType>,
+
+    #[serde(rename = "loop")]
+    pub loop_mode: Option<LoopMode>,
+
+    #[serde(rename = "firstFrame")]
+    pub first_frame: Option<u64>,
+
+    #[serde(rename = "lastFrame")]
+    pub last_frame: Option<u64>,
+
+    #[serde(rename = "matrix")]
+    pub matrices: Option<Matrices>,
+
+    /// The 3D transform, which replaces `matrix` for instances moved with the 3D tools.
+    #[serde(rename = "matrix3D")]
+    pub matrices_3d: Option<Matrices3D>,
+
+    #[serde(rename = "transformationPoint")]
+    pub transformation_point: Option<TransformationPoint>,
+
+    #[serde(rename = "color")]
+    pub colors: Option<Colors>,
+
+    pub filters: Option<Filters>,
+
+    #[serde(rename = "blendMode")]
+    pub blend_mode: Option<BlendMode>,
+}
+
+impl DomSymbolInstance {
+    /// Get the matrix of this instance, or the identity if it is missing.
+    ///
+    /// Instances with only a 3D transform use its 2D part.
+    pub fn get_matrix(&self) -> Matrix {
+        self.matrices
+            .as_ref()
+            .map(|matrices| matrices.get_matrix())
+            .or_else(|| self.get_matrix_3d().map(|matrix| matrix.to_matrix()))
+            .unwrap_or_default()
+    }
+
+    /// Get the 3D transform of this instance, if it was moved with the 3D tools.
+    pub fn get_matrix_3d(&self) -> Option<Matrix3D> {
+        self.matrices_3d
+            .as_ref()
+            .and_then(|matrices| matrices.get_matrix())
+    }
+
+    /// Get the color transform of this instance, or the identity if it is missing.
+    pub fn get_color_transform(&self) -> ColorTransform {
+        self.colors
+            .as_ref()
+            .map(|colors| colors.get_color_transform())
+            .unwrap_or_default()
+    }
+
+    /// Get the filters of this instance, in the order they are applied.
+    pub fn get_filters(&self) -> impl Iterator<Item = &Filter> {
+        self.filters
+            .iter()
+            .flat_map(|filters| filters.get_filters())
+    }
+
+    /// Get the blend mode. Instances without a blend mode are drawn normally.
+    pub fn get_blend_mode(&self) -> BlendMode {
+        self.blend_mode.unwrap_or(BlendMode::Normal)
+    }
+
+    /// Get the transformation point of this instance, or the origin if it is missing.
+    pub fn get_transformation_point(&self) -> Point {
+        self.transformation_point
+            .as_ref()
+            .map(|transformation_point| transformation_point.point)
+            .unwrap_or_default()
+    }
+
+    /// Get the symbol type. Instances without a symbol type are movie clips.
+    pub fn get_symbol_type(&self) -> SymbolType {
+        self.symbol_type.unwrap_or(SymbolType::MovieClip)
+    }
+
+    /// Get the loop mode. Instances without a loop mode loop.
+    pub fn get_loop_mode(&self) -> LoopMode {
+        self.loop_mode.unwrap_or(LoopMode::Loop)
+    }
+
+    /// Get the first frame to play, 0-indexed.
+    pub fn get_first_frame(&self) -> usize {
+        self.first_frame.unwrap_or(0) as usize
+    }
+
+    /// Get the frame of the symbol shown by this instance as a graphic,
+    /// `elapsed` frames after the keyframe holding it began.
+    pub fn get_graphic_frame(&self, elapsed: usize, num_frames: usize) -> usize {
+        let last_frame = self.last_frame.map(|last_frame| last_frame as usize);
+        self.get_loop_mode()
+            .get_frame(self.get_first_frame(), last_frame, elapsed, num_frames)
+    }
+
+    /// Calculate the bounding box of this instance, placed in a symbol nested in the symbols named by `ancestors`.
+    ///
+    /// If `include_filters` is set, the bounding box grows by the extent of the filters of this instance and nested instances.
+    /// Filters are not scaled by the matrix of their instance, so their extent is added after it is applied.
+    pub fn calc_bounding_box(
+        &self,
+        fla: &Fla,
+        layer_policy: &LayerPolicy,
+        ancestors: &[&str],
+        include_filters: bool,
+    ) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        let bounding_box = fla
+            .get_symbol(&self.library_item_name)?
+            .calc_nested_bounding_box(fla, layer_policy, ancestors, include_filters)?;
+
+        // 3D instances are projected as if they were placed on the stage.
+        let bounding_box = match self.get_matrix_3d() {
+            Some(matrix_3d) => {
+                let perspective = fla.dom_document.get_perspective();
+                matrix_3d.project_twips_box(&bounding_box, &perspective)
+            }
+            None => self.get_matrix().transform_twips_box(&bounding_box),
+        };
+        if !include_filters {
+            return Some(bounding_box);
+        }
+
+        // Each filter is applied to the output of the filters before it, so their extents add up.
+        let (x, y) = self
+            .get_filters()
+            .map(|filter| filter.get_extent())
+            .fold((0.0, 0.0), |(x, y), extent| (x + extent.0, y + extent.1));
+
+        Some(bounding_box.inflate(x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum SymbolType {
+    #[serde(rename = "movie clip")]
+    MovieClip,
+
+    #[serde(rename = "graphic")]
+    Graphic,
+
+    #[serde(rename = "button")]
+    Button,
+}
+
+/// How an instance is composited with what is below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum BlendMode {
+    #[serde(rename = "normal")]
+    Normal,
+
+    /// Draws the instance to its own layer first, so `Alpha` and `Erase` children only affect the instance.
+    #[serde(rename = "layer")]
+    Layer,
+
+    #[serde(rename = "multiply")]
+    Multiply,
+
+    #[serde(rename = "screen")]
+    Screen,
+
+    #[serde(rename = "overlay")]
+    Overlay,
+
+    #[serde(rename = "hardlight")]
+    HardLight,
+
+    #[serde(rename = "lighten")]
+    Lighten,
+
+    #[serde(rename = "darken")]
+    Darken,
+
+    #[serde(rename = "difference")]
+    Difference,
+
+    #[serde(rename = "add")]
+    Add,
+
+    #[serde(rename = "subtract")]
+    Subtract,
+
+    #[serde(rename = "invert")]
+    Invert,
+
+    /// Uses the alpha of the instance as a mask for the layer below.
+    #[serde(rename = "alpha")]
+    Alpha,
+
+    /// Erases the layer below where the instance is opaque.
+    #[serde(rename = "erase")]
+    Erase,
+}
+
+impl BlendMode {
+    /// Composite a premultiplied ARGB pixel onto another.
+    pub fn blend(self, src: u32, dst: u32) -> u32 {
+        let [sa, sr, sg, sb] = to_floats(src);
+        let [da, dr, dg, db] = to_floats(dst);
+
+        // Blend unpremultiplied colors, then composite the result over the backdrop
+        let blend_channel = |s: f64, d: f64| -> f64 {
+            let s = if sa > 0.0 { s / sa } else { 0.0 };
+            let d = if da > 0.0 { d / da } else { 0.0 };
+            let hard_light = |s: f64, d: f64| {
+                if s <= 0.5 {
+                    2.0 * s * d
+                } else {
+                    1.0 - 2.0 * (1.0 - s) * (1.0 - d)
+                }
+            };
+
+            match self {
+                Self::Multiply => s * d,
+                Self::Screen => s + d - s * d,
+                Self::Overlay => hard_light(d, s),
+                Self::HardLight => hard_light(s, d),
+                Self::Lighten => s.max(d),
+                Self::Darken => s.min(d),
+                Self::Difference => (s - d).abs(),
+                Self::Add => (s + d).min(1.0),
+                Self::Subtract => (d - s).max(0.0),
+                Self::Invert => 1.0 - d,
+                Self::Normal | Self::Layer | Self::Alpha | Self::Erase => s,
+            }
+        };
+
+        let out = match self {
+            Self::Alpha => [da * sa, dr * sa, dg * sa, db * sa],
+            Self::Erase => [
+                da * (1.0 - sa),
+                dr * (1.0 - sa),
+                dg * (1.0 - sa),
+                db * (1.0 - sa),
+            ],
+            Self::Normal | Self::Layer => {
+                let inv = 1.0 - sa;
+                [sa + da * inv, sr + dr * inv, sg + dg * inv, sb + db * inv]
+            }
+            Self::Invert | Self::Subtract => {
+                // These only change the backdrop where the instance is drawn, and keep its alpha
+                let channel = |s: f64, d: f64| d * (1.0 - sa) + blend_channel(s, d) * da * sa;
+                [da, channel(sr, dr), channel(sg, dg), channel(sb, db)]
+            }
+            _ => {
+                let alpha = sa + da - sa * da;
+                let channel = |s: f64, d: f64| {
+                    s * (1.0 - da) + d * (1.0 - sa) + blend_channel(s, d) * sa * da
+                };
+                [alpha, channel(sr, dr), channel(sg, dg), channel(sb, db)]
+            }
+        };
+
+        let mut bytes = [0; 4];
+        for (byte, channel) in bytes.iter_mut().zip(out.iter()) {
+            *byte = (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        u32::from_be_bytes(bytes)
+    }
+}
+
+fn to_floats(pixel: u32) -> [f64; 4] {
+    let [a, r, g, b] = pixel.to_be_bytes();
+    [a, r, g, b].map(|channel| f64::from(channel) / 255.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum LoopMode {
+    #[serde(rename = "loop")]
+    Loop,
+
+    #[serde(rename = "play once")]
+    PlayOnce,
+
+    #[serde(rename = "single frame")]
+    SingleFrame,
+
+    #[serde(rename = "loop reverse")]
+    LoopReverse,
+
+    #[serde(rename = "play once reverse")]
+    PlayOnceReverse,
+}
+
+impl LoopMode {
+    /// Get the frame shown `elapsed` frames after starting at `first_frame`.
+    ///
+    /// Looping modes wrap around to the first frame of the symbol,
+    /// unless a `last_frame` is given, in which case they wrap between `first_frame` and `last_frame`.
+    pub fn get_frame(
+        self,
+        first_frame: usize,
+        last_frame: Option<usize>,
+        elapsed: usize,
+        num_frames: usize,
+    ) -> usize {
+        if num_frames == 0 {
+            return 0;
+        }
+
+        let first_frame = first_frame.min(num_frames - 1);
+        let (start, end) = match last_frame {
+            Some(last_frame) => (first_frame, last_frame.clamp(first_frame, num_frames - 1)),
+            None => (0, num_frames - 1),
+        };
+        let len = end - start + 1;
+
+        match self {
+            Self::Loop => start + (first_frame - start + elapsed) % len,
+            Self::PlayOnce => first_frame.saturating_add(elapsed).min(end),
+            Self::SingleFrame => first_frame,
+            Self::LoopReverse => start + (first_frame - start + len - elapsed % len) % len,
+            Self::PlayOnceReverse => first_frame.saturating_sub(elapsed).max(start),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TransformationPoint {
+    #[serde(rename = "Point")]
+    pub point: Point,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn loop_frames() {
+        let frames: Vec<_> = (0..6)
+            .map(|elapsed| LoopMode::Loop.get_frame(2, None, elapsed, 4))
+            .collect();
+        assert_eq!(frames, [2, 3, 0, 1, 2, 3]);
+
+        let frames: Vec<_> = (0..6)
+            .map(|elapsed| LoopMode::Loop.get_frame(1, Some(2), elapsed, 4))
+            .collect();
+        assert_eq!(frames, [1, 2, 1, 2, 1, 2]);
+
+        let frames: Vec<_> = (0..6)
+            .map(|elapsed| LoopMode::LoopReverse.get_frame(1, None, elapsed, 4))
+            .collect();
+        assert_eq!(frames, [1, 0, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn play_once_frames() {
+        let frames: Vec<_> = (0..6)
+            .map(|elapsed| LoopMode::PlayOnce.get_frame(2, None, elapsed, 4))
+            .collect();
+        assert_eq!(frames, [2, 3, 3, 3, 3, 3]);
+
+        let frames: Vec<_> = (0..4)
+            .map(|elapsed| LoopMode::PlayOnceReverse.get_frame(2, None, elapsed, 4))
+            .collect();
+        assert_eq!(frames, [2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn blend_modes() {
+        let red = 0xFF_FF_00_00;
+        let gray = 0xFF_80_80_80;
+        let half_white = 0x80_80_80_80;
+
+        assert_eq!(BlendMode::Normal.blend(half_white, red), 0xFF_FF_80_80);
+        assert_eq!(BlendMode::Multiply.blend(gray, red), 0xFF_80_00_00);
+        assert_eq!(BlendMode::Screen.blend(gray, red), 0xFF_FF_80_80);
+        assert_eq!(BlendMode::Subtract.blend(gray, red), 0xFF_7F_00_00);
+        assert_eq!(BlendMode::Invert.blend(gray, red), 0xFF_00_FF_FF);
+        assert_eq!(BlendMode::Erase.blend(red, gray), 0);
+        assert_eq!(BlendMode::Alpha.blend(half_white, gray), 0x80_40_40_40);
+        assert_eq!(BlendMode::Add.blend(0, red), red);
+    }
+
+    #[test]
+    fn single_frame() {
+        for elapsed in 0..4 {
+            assert_eq!(LoopMode::SingleFrame.get_frame(2, None, elapsed, 4), 2);
+        }
+
+        assert_eq!(LoopMode::SingleFrame.get_frame(7, None, 0, 4), 3);
+        assert_eq!(LoopMode::Loop.get_frame(0, None, 3, 0), 0);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::types::{
+    dom_shape::parse_rgb,
+    matrix::TWIPS_PER_PIXEL,
+    Matrices,
+    Matrix,
+};
+use std::borrow::Cow;
+
+/// The default font size, in points.
+const DEFAULT_SIZE: f64 = 12.0;
+
+/// The default line spacing, in points.
+const DEFAULT_LINE_SPACING: f64 = 2.0;
+
+/// A text field.
+///
+/// This is used for static, dynamic and input text, which share the same layout.
+/// Attributes that do not apply to a kind of text field are missing.
+#[derive(Debug, serde::Deserialize)]
+pub struct DomText {
+    pub name: Option<String>,
+    pub selected: Option<bool>,
+
+    /// The horizontal offset of the text box, in pixels.
+    pub left: Option<f64>,
+
+    /// The width of the text box, in pixels.
+    pub width: Option<f64>,
+
+    /// The height of the text box, in pixels.
+    pub height: Option<f64>,
+
+    #[serde(rename = "autoExpand")]
+    pub auto_expand: Option<bool>,
+
+    #[serde(rename = "isSelectable")]
+    pub is_selectable: Option<bool>,
+
+    #[serde(rename = "fontRenderingMode")]
+    pub font_rendering_mode: Option<String>,
+
+    #[serde(rename = "lineType")]
+    pub line_type: Option<LineType>,
+
+    #[serde(rename = "maxCharacters")]
+    pub max_characters: Option<u64>,
+
+    #[serde(rename = "renderAsHTML")]
+    pub render_as_html: Option<bool>,
+
+    pub border: Option<bool>,
+
+    #[serde(rename = "variableName")]
+    pub variable_name: Option<String>,
+
+    #[serde(rename = "matrix")]
+    pub matrices: Option<Matrices>,
+
+    #[serde(rename = "textRuns")]
+    pub text_runs: Option<TextRuns>,
+}
+
+impl DomText {
+    /// Get the matrix of this text field, or the identity if it is missing.
+    pub fn get_matrix(&self) -> Matrix {
+        self.matrices
+            .as_ref()
+            .map(|matrices| matrices.get_matrix())
+            .unwrap_or_default()
+    }
+
+    pub fn get_text_runs(&self) -> &[DomTextRun] {
+        self.text_runs
+            .as_ref()
+            .map(|text_runs| text_runs.text_runs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Get the characters of every text run, joined together.
+    ///
+    /// Lines are separated by `\r`, like in the XFL.
+    pub fn get_characters(&self) -> String {
+        self.get_text_runs()
+            .iter()
+            .map(|text_run| text_run.characters.as_str())
+            .collect()
+    }
+
+    /// Get the bounds of the text box, in pixels, before the matrix is applied.
+    pub fn get_bounds(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        let left = self.left.unwrap_or(0.0);
+        Some(euclid::Box2D::new(
+            euclid::Point2D::new(left, 0.0),
+            euclid::Point2D::new(left + self.width?, self.height?),
+        ))
+    }
+
+    pub fn calc_bounding_box(&self) -> Option<euclid::Box2D<f64, euclid::UnknownUnit>> {
+        let bounds = self.get_bounds()?;
+        let bounding_box = bounds.scale(TWIPS_PER_PIXEL, TWIPS_PER_PIXEL);
+
+        Some(self.get_matrix().transform_twips_box(&bounding_box))
+    }
+}
+
+/// How the lines of a dynamic or input text field are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum LineType {
+    #[serde(rename = "single line")]
+    SingleLine,
+
+    #[serde(rename = "multiline")]
+    Multiline,
+
+    #[serde(rename = "multiline no wrap")]
+    MultilineNoWrap,
+
+    #[serde(rename = "password")]
+    Password,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TextRuns {
+    #[serde(rename = "DOMTextRun", default)]
+    pub text_runs: Vec<DomTextRun>,
+}
+
+/// A run of characters sharing the same text attributes.
+#[derive(Debug, serde::Deserialize)]
+pub struct DomTextRun {
+    #[serde(default)]
+    pub characters: String,
+
+    #[serde(rename = "textAttrs")]
+    pub text_attrs: Option<TextAttrs>,
+}
+
+impl DomTextRun {
+    /// Get the text attributes of this run, or the defaults if they are missing.
+    pub fn get_text_attrs(&self) -> Cow<'_, DomTextAttrs> {
+        match self.text_attrs.as_ref() {
+            Some(text_attrs) => Cow::Borrowed(&text_attrs.dom_text_attrs),
+            None => Cow::Owned(DomTextAttrs::default()),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TextAttrs {
+    #[serde(rename = "DOMTextAttrs")]
+    pub dom_text_attrs: DomTextAttrs,
+}
+
+/// The formatting of a text run.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct DomTextAttrs {
+    pub alignment: Option<Alignment>,
+
+    #[serde(rename = "aliasText")]
+    pub alias_text: Option<bool>,
+
+    #[serde(rename = "autoKern")]
+    pub auto_kern: Option<bool>,
+
+    /// The PostScript name of the font.
+    pub face: Option<String>,
+
+    /// The font size, in points.
+    pub size: Option<f64>,
+
+    #[serde(rename = "bitmapSize")]
+    pub bitmap_size: Option<f64>,
+
+    #[serde(rename = "fillColor")]
+    pub fill_color: Option<String>,
+
+    pub alpha: Option<f64>,
+
+    /// The extra space between characters, in points.
+    #[serde(rename = "letterSpacing")]
+    pub letter_spacing: Option<f64>,
+
+    /// The extra space between lines, in points.
+    #[serde(rename = "lineSpacing")]
+    pub line_spacing: Option<f64>,
+
+    pub indent: Option<f64>,
+
+    #[serde(rename = "leftMargin")]
+    pub left_margin: Option<f64>,
+
+    #[serde(rename = "rightMargin")]
+    pub right_margin: Option<f64>,
+
+    #[serde(rename = "characterPosition")]
+    pub character_position: Option<String>,
+
+    pub url: Option<String>,
+    pub target: Option<String>,
+}
+
+impl DomTextAttrs {
+    pub fn get_alignment(&self) -> Alignment {
+        self.alignment.unwrap_or(Alignment::Left)
+    }
+
+    pub fn get_face(&self) -> Option<&str> {
+        self.face.as_deref()
+    }
+
+    pub fn get_size(&self) -> f64 {
+        self.size.unwrap_or(DEFAULT_SIZE)
+    }
+
+    /// Get the fill color. Missing colors are black.
+    pub fn get_fill_color(&self) -> Option<(u8, u8, u8)> {
+        match self.fill_color.as_deref() {
+            Some(fill_color) => parse_rgb(fill_color),
+            None => Some((0, 0, 0)),
+        }
+    }
+
+    pub fn get_alpha(&self) -> f64 {
+        self.alpha.unwrap_or(1.0)
+    }
+
+    pub fn get_letter_spacing(&self) -> f64 {
+        self.letter_spacing.unwrap_or(0.0)
+    }
+
+    pub fn get_line_spacing(&self) -> f64 {
+        self.line_spacing.unwrap_or(DEFAULT_LINE_SPACING)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum Alignment {
+    #[serde(rename = "left")]
+    Left,
+
+    #[serde(rename = "center")]
+    Center,
+
+    #[serde(rename = "right")]
+    Right,
+
+    #[serde(rename = "justify")]
+    Justify,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xml;
+
+    const STATIC_TEXT: &str = r##"<DOMStaticText left="-2" width="120.5" height="30" autoExpand="true" isSelectable="false">
+    <matrix><Matrix tx="10" ty="20"/></matrix>
+    <textRuns>
+        <DOMTextRun>
+            <characters>Hello </characters>
+            <textAttrs>
+                <DOMTextAttrs alignment="center" face="ArialMT" size="24" fillColor="#FF0080" alpha="0.5" letterSpacing="1.5" lineSpacing="4"/>
+            </textAttrs>
+        </DOMTextRun>
+        <DOMTextRun>
+            <characters>world&#xD;again</characters>
+        </DOMTextRun>
+    </textRuns>
+</DOMStaticText>"##;
+
+    #[test]
+    fn parse_static_text() {
+        let text: DomText = xml::from_str(STATIC_TEXT).unwrap();
+
+        assert_eq!(text.get_characters(), "Hello world\ragain");
+        assert_eq!(text.get_bounds().unwrap().width(), 120.5);
+        assert_eq!(text.get_matrix().tx, 10.0);
+
+        let text_runs = text.get_text_runs();
+        let text_attrs = text_runs[0].get_text_attrs();
+        assert_eq!(text_attrs.get_alignment(), Alignment::Center);
+        assert_eq!(text_attrs.get_face(), Some("ArialMT"));
+        assert_eq!(text_attrs.get_size(), 24.0);
+        assert_eq!(text_attrs.get_fill_color(), Some((0xFF, 0x00, 0x80)));
+        assert_eq!(text_attrs.get_alpha(), 0.5);
+        assert_eq!(text_attrs.get_letter_spacing(), 1.5);
+        assert_eq!(text_attrs.get_line_spacing(), 4.0);
+
+        let text_attrs = text_runs[1].get_text_attrs();
+        assert_eq!(text_attrs.get_alignment(), Alignment::Left);
+        assert_eq!(text_attrs.get_size(), DEFAULT_SIZE);
+        assert_eq!(text_attrs.get_fill_color(), Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn preserve_characters_whitespace() {
+        let text: DomText = xml::from_str(
+            r#"<DOMDynamicText>
+                <textRuns>
+                    <DOMTextRun><characters> a &amp; b </characters></DOMTextRun>
+                    <DOMTextRun><characters>  </characters></DOMTextRun>
+                </textRuns>
+            </DOMDynamicText>"#,
+        )
+        .unwrap();
+
+        assert_eq!(text.get_characters(), " a & b   ");
+    }
+}
@@ -1,4 +1,10 @@
-use crate::types::DomLayer;
+use crate::types::{
+    action_script::FrameScript,
+    dom_frame::LabelType,
+    dom_layer::LayerType,
+    DomLayer,
+};
+use std::ops::Range;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct DomTimeline {
@@ -15,6 +21,233 @@ impl DomTimeline {
     pub fn get_layer(&self, index: usize) -> Option<&DomLayer> {
         self.get_layers().get(index)
     }
+
+    /// Get the number of frames in this timeline, which is the number of frames in its longest layer.
+    pub fn num_frames(&self) -> usize {
+        self.get_layers()
+            .iter()
+            .map(|layer| layer.num_frames())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the motion guide of a layer, if it is guided.
+    pub fn get_motion_guide(&self, layer: &DomLayer) -> Option<&DomLayer> {
+        self.get_layer(layer.parent_layer_index?)
+            .filter(|parent| parent.is_guide())
+    }
+
+    /// Check if a layer is a motion guide, guiding other layers.
+    pub fn is_motion_guide(&self, layer_index: usize) -> bool {
+        self.get_layer(layer_index)
+            .is_some_and(|layer| layer.is_guide())
+            && self
+                .get_layers()
+                .iter()
+                .any(|layer| layer.parent_layer_index == Some(layer_index))
+    }
+
+    /// Get the parent of a layer, like the folder holding it.
+    ///
+    /// Parents come before their children, so other parents are ignored.
+    pub fn get_parent_index(&self, layer_index: usize) -> Option<usize> {
+        self.get_layer(layer_index)?
+            .parent_layer_index
+            .filter(|parent_index| *parent_index < layer_index)
+    }
+
+    /// Get the kind of a layer, including whether it is guided or masked by its parent.
+    pub fn get_layer_type(&self, layer_index: usize) -> Option<LayerType> {
+        let layer_type = self.get_layer(layer_index)?.get_layer_type();
+        if layer_type != LayerType::Normal {
+            return Some(layer_type);
+        }
+
+        let parent_type = self
+            .get_parent_index(layer_index)
+            .and_then(|parent_index| self.get_layer(parent_index))
+            .map(|parent| parent.get_layer_type());
+        Some(match parent_type {
+            Some(LayerType::Guide) => LayerType::Guided,
+            Some(LayerType::Mask) => LayerType::Masked,
+            _ => LayerType::Normal,
+        })
+    }
+
+    /// Get the mask layer masking a layer, if it is masked.
+    pub fn get_mask_index(&self, layer_index: usize) -> Option<usize> {
+        self.get_parent_index(layer_index).filter(|parent_index| {
+            self.get_layer(*parent_index)
+                .is_some_and(|parent| parent.is_mask())
+        })
+    }
+
+    /// Get the layers as a tree, where folders, guides and masks hold their children.
+    ///
+    /// Layers are ordered from top to bottom, like in the editor.
+    pub fn get_layer_tree(&self) -> Vec<LayerNode<'_>> {
+        self.get_layer_children(None)
+    }
+
+    fn get_layer_children(&self, parent_index: Option<usize>) -> Vec<LayerNode<'_>> {
+        self.get_layers()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.get_parent_index(*index) == parent_index)
+            .map(|(index, layer)| LayerNode {
+                index,
+                layer,
+                layer_type: self.get_layer_type(index).unwrap_or(LayerType::Normal),
+                children: self.get_layer_children(Some(index)),
+            })
+            .collect()
+    }
+
+    /// Check if a layer and the folders holding it are shown in the editor.
+    pub fn is_layer_visible(&self, layer_index: usize) -> bool {
+        let mut index = Some(layer_index);
+        while let Some(layer_index) = index {
+            match self.get_layer(layer_index) {
+                Some(layer) if layer.is_visible() => index = self.get_parent_index(layer_index),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Check if the content of a layer is rendered.
+    ///
+    /// Folders and cameras have no content, and guides and hidden layers are rendered depending on the policy.
+    pub fn should_render_layer(&self, layer_index: usize, policy: &LayerPolicy) -> bool {
+        let layer_type = match self.get_layer_type(layer_index) {
+            Some(layer_type) => layer_type,
+            None => return false,
+        };
+
+        match layer_type {
+            LayerType::Folder | LayerType::Camera => return false,
+            LayerType::Guide if !policy.include_guides => return false,
+            _ => {}
+        }
+
+        policy.include_hidden || self.is_layer_visible(layer_index)
+    }
+
+    /// Get the scripts of every keyframe, ordered by layer and frame.
+    ///
+    /// Scripts are not attributed to a symbol. Use [`crate::Fla::get_scripts`] for that.
+    pub fn get_scripts(&self) -> Vec<FrameScript<'_>> {
+        self.get_layers()
+            .iter()
+            .enumerate()
+            .flat_map(|(layer_index, layer)| {
+                layer.get_frames().iter().filter_map(move |frame| {
+                    Some(FrameScript {
+                        symbol: None,
+                        timeline: &self.name,
+                        layer_index,
+                        layer_name: &layer.name,
+                        frame_index: frame.index as usize,
+                        source: frame.get_script()?,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Get the labels, comments and anchors of every layer, ordered by frame.
+    pub fn get_labels(&self) -> Vec<FrameLabel<'_>> {
+        let mut ret: Vec<_> = self
+            .get_layers()
+            .iter()
+            .enumerate()
+            .flat_map(|(layer_index, layer)| {
+                layer.get_frames().iter().filter_map(move |frame| {
+                    let (name, label_type) = frame.get_label()?;
+                    Some(FrameLabel {
+                        name,
+                        label_type,
+                        layer_index,
+                        frame_index: frame.index as usize,
+                    })
+                })
+            })
+            .collect();
+        ret.sort_by_key(|label| (label.frame_index, label.layer_index));
+
+        ret
+    }
+
+    /// Find a frame label or anchor by name. Comments are not labels.
+    pub fn get_label(&self, name: &str) -> Option<FrameLabel<'_>> {
+        self.get_labels()
+            .into_iter()
+            .find(|label| label.is_label() && label.name == name)
+    }
+
+    /// Get the frames of a label, 0-indexed.
+    ///
+    /// A label spans from its frame to the next label, or to the end of the timeline.
+    pub fn get_label_range(&self, name: &str) -> Option<Range<usize>> {
+        let start = self.get_label(name)?.frame_index;
+        let end = self
+            .get_labels()
+            .iter()
+            .filter(|label| label.is_label() && label.frame_index > start)
+            .map(|label| label.frame_index)
+            .next()
+            .unwrap_or_else(|| self.num_frames());
+
+        Some(start..end.max(start + 1))
+    }
+}
+
+/// A layer and the layers it holds.
+#[derive(Debug, Clone)]
+pub struct LayerNode<'a> {
+    pub index: usize,
+    pub layer: &'a DomLayer,
+    pub layer_type: LayerType,
+    pub children: Vec<LayerNode<'a>>,
+}
+
+/// Which layers are sampled and rendered.
+///
+/// By default, guides are skipped and hidden layers are rendered, like publishing from Animate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerPolicy {
+    pub include_guides: bool,
+    pub include_hidden: bool,
+}
+
+impl Default for LayerPolicy {
+    fn default() -> Self {
+        Self {
+            include_guides: false,
+            include_hidden: true,
+        }
+    }
+}
+
+/// A label of a keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameLabel<'a> {
+    pub name: &'a str,
+    pub label_type: LabelType,
+
+    /// The layer holding the keyframe.
+    pub layer_index: usize,
+
+    /// The first frame of the keyframe, 0-indexed.
+    pub frame_index: usize,
+}
+
+impl FrameLabel<'_> {
+    /// Check if this is a frame label or anchor, rather than a comment.
+    pub fn is_label(&self) -> bool {
+        self.label_type != LabelType::Comment
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -22,3 +255,92 @@ pub struct Layers {
     #[serde(rename = "DOMLayer", default)]
     pub dom_layers: Vec<DomLayer>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn label_ranges() {
+        let timeline: DomTimeline = quick_xml::de::from_str(
+            r##"<DOMTimeline name="a">
+                <layers>
+                    <DOMLayer name="labels" color="#000000">
+                        <frames>
+                            <DOMFrame index="0" duration="4" keyMode="9728" name="walk_start"><elements/></DOMFrame>
+                            <DOMFrame index="4" duration="2" keyMode="9728" name="todo" labelType="comment"><elements/></DOMFrame>
+                            <DOMFrame index="6" duration="4" keyMode="9728" name="run" labelType="anchor"><elements/></DOMFrame>
+                        </frames>
+                    </DOMLayer>
+                    <DOMLayer name="art" color="#000000">
+                        <frames>
+                            <DOMFrame index="0" duration="12" keyMode="9728"><elements/></DOMFrame>
+                        </frames>
+                    </DOMLayer>
+                </layers>
+            </DOMTimeline>"##,
+        )
+        .unwrap();
+
+        let labels = timeline.get_labels();
+        assert_eq!(
+            labels
+                .iter()
+                .map(|label| (label.name, label.label_type, label.frame_index))
+                .collect::<Vec<_>>(),
+            [
+                ("walk_start", LabelType::Name, 0),
+                ("todo", LabelType::Comment, 4),
+                ("run", LabelType::Anchor, 6),
+            ]
+        );
+
+        assert_eq!(timeline.get_label_range("walk_start"), Some(0..6));
+        assert_eq!(timeline.get_label_range("run"), Some(6..12));
+        assert_eq!(timeline.get_label_range("todo"), None);
+    }
+
+    #[test]
+    fn layer_tree_and_policy() {
+        let timeline: DomTimeline = quick_xml::de::from_str(
+            r##"<DOMTimeline name="a">
+                <layers>
+                    <DOMLayer name="folder" color="#000000" layerType="folder" visible="false"><frames/></DOMLayer>
+                    <DOMLayer name="guide" color="#000000" layerType="guide" parentLayerIndex="0"><frames/></DOMLayer>
+                    <DOMLayer name="guided" color="#000000" parentLayerIndex="1"><frames/></DOMLayer>
+                    <DOMLayer name="art" color="#000000"><frames/></DOMLayer>
+                </layers>
+            </DOMTimeline>"##,
+        )
+        .unwrap();
+
+        let tree = timeline.get_layer_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].layer_type, LayerType::Folder);
+        assert_eq!(tree[0].children[0].layer_type, LayerType::Guide);
+        assert_eq!(tree[0].children[0].children[0].layer.name, "guided");
+        assert_eq!(
+            tree[0].children[0].children[0].layer_type,
+            LayerType::Guided
+        );
+        assert_eq!(tree[1].index, 3);
+
+        // The guided layer is hidden by its folder.
+        assert!(!timeline.is_layer_visible(2));
+        assert!(timeline.is_layer_visible(3));
+
+        let rendered = |policy: &LayerPolicy| {
+            (0..4)
+                .filter(|i| timeline.should_render_layer(*i, policy))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rendered(&LayerPolicy::default()), [2, 3]);
+        assert_eq!(
+            rendered(&LayerPolicy {
+                include_guides: true,
+                include_hidden: false,
+            }),
+            [3]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::types::Point;
+use std::f64::consts::PI;
+
+/// The `<tweens>` element of a keyframe, holding the eases of a tween.
+#[derive(Debug, serde::Deserialize)]
+pub struct Tweens {
+    #[serde(rename = "$value", default)]
+    pub eases: Vec<TweenEase>,
+}
+
+impl Tweens {
+    /// Get the ease curve of a property, if this keyframe defines one.
+    pub fn get_curve(&self, target: EaseTarget) -> Option<EaseCurve> {
+        self.eases
+            .iter()
+            .find(|ease| ease.get_target() == Some(target))
+            .and_then(|ease| ease.get_curve())
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub enum TweenEase {
+    #[serde(rename = "CustomEase")]
+    CustomEase(CustomEase),
+
+    #[serde(rename = "Ease")]
+    Ease(Ease),
+
+    #[serde(other)]
+    Unknown,
+}
+
+impl TweenEase {
+    pub fn get_target(&self) -> Option<EaseTarget> {
+        match self {
+            Self::CustomEase(ease) => Some(ease.target.unwrap_or(EaseTarget::All)),
+            Self::Ease(ease) => Some(ease.target.unwrap_or(EaseTarget::All)),
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn get_curve(&self) -> Option<EaseCurve> {
+        match self {
+            Self::CustomEase(ease) => Some(ease.get_curve()),
+            Self::Ease(ease) => Some(ease.get_curve()),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// The properties of a classic tween that can be eased separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum EaseTarget {
+    #[serde(rename = "all")]
+    All,
+
+    #[serde(rename = "position")]
+    Position,
+
+    #[serde(rename = "rotation")]
+    Rotation,
+
+    #[serde(rename = "scale")]
+    Scale,
+
+    #[serde(rename = "color")]
+    Color,
+
+    #[serde(rename = "filters")]
+    Filters,
+}
+
+/// An ease drawn in the custom ease editor.
+///
+/// The points are the anchors and control points of cubic Bézier curves, like `anchor (control control anchor)*`.
+/// X is the time and Y is the progress, both from 0 to 1.
+#[derive(Debug, serde::Deserialize)]
+pub struct CustomEase {
+    pub target: Option<EaseTarget>,
+
+    #[serde(rename = "Point", default)]
+    pub points: Vec<Point>,
+}
+
+impl CustomEase {
+    pub fn get_curve(&self) -> EaseCurve {
+        if self.points.len() < 4 {
+            return EaseCurve::Linear;
+        }
+
+        EaseCurve::Bezier(self.points.iter().map(|point| (point.x, point.y)).collect())
+    }
+}
+
+/// A preset ease, like `quadIn`, or a classic ease with an intensity.
+#[derive(Debug, serde::Deserialize)]
+pub struct Ease {
+    pub target: Option<EaseTarget>,
+    pub method: Option<String>,
+
+    /// The intensity of a classic ease, from -100 (ease in) to 100 (ease out).
+    pub intensity: Option<f64>,
+}
+
+impl Ease {
+    pub fn get_curve(&self) -> EaseCurve {
+        let method = match self.method.as_deref() {
+            Some(method) => method,
+            None => return EaseCurve::Classic(self.intensity.unwrap_or(0.0) / 100.0),
+        };
+
+        let (name, direction) = if let Some(name) = strip_suffix_ignore_case(method, "InOut") {
+            (name, EaseDirection::InOut)
+        } else if let Some(name) = strip_suffix_ignore_case(method, "In") {
+            (name, EaseDirection::In)
+        } else if let Some(name) = strip_suffix_ignore_case(method, "Out") {
+            (name, EaseDirection::Out)
+        } else {
+            (method, EaseDirection::In)
+        };
+
+        let function = match name.to_ascii_lowercase().as_str() {
+            "classic" => return EaseCurve::Classic(self.intensity.unwrap_or(0.0) / 100.0),
+            "quad" => EaseFunction::Quad,
+            "cubic" => EaseFunction::Cubic,
+            "quart" => EaseFunction::Quart,
+            "quint" => EaseFunction::Quint,
+            "sine" => EaseFunction::Sine,
+            "circ" => EaseFunction::Circ,
+            "back" => EaseFunction::Back,
+            "bounce" => EaseFunction::Bounce,
+            "elastic" => EaseFunction::Elastic,
+            _ => return EaseCurve::Linear,
+        };
+
+        EaseCurve::Preset(function, direction)
+    }
+}
+
+fn strip_suffix_ignore_case<'a>(value: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = value.len().checked_sub(suffix.len())?;
+    if value.is_char_boundary(split) && value[split..].eq_ignore_ascii_case(suffix) {
+        Some(&value[..split])
+    } else {
+        None
+    }
+}
+
+/// A curve mapping the time of a tween to its progress, both from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EaseCurve {
+    Linear,
+
+    /// The quadratic ease of classic tweens, from -1 (ease in) to 1 (ease out).
+    Classic(f64),
+
+    /// Cubic Bézier curves, like a [`CustomEase`].
+    Bezier(Vec<(f64, f64)>),
+
+    Preset(EaseFunction, EaseDirection),
+}
+
+impl EaseCurve {
+    /// Get the progress at a time from 0 to 1.
+    pub fn evaluate(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::Classic(ease) => {
+                let ease = ease.clamp(-1.0, 1.0);
+                t + ease * t * (1.0 - t)
+            }
+            Self::Bezier(points) => evaluate_bezier(points, t),
+            Self::Preset(function, direction) => match direction {
+                EaseDirection::In => function.ease_in(t),
+                EaseDirection::Out => 1.0 - function.ease_in(1.0 - t),
+                EaseDirection::InOut => {
+                    if t < 0.5 {
+                        function.ease_in(t * 2.0) / 2.0
+                    } else {
+                        1.0 - function.ease_in(2.0 - t * 2.0) / 2.0
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EaseFunction {
+    Quad,
+    Cubic,
+    Quart,
+    Quint,
+    Sine,
+    Circ,
+    Back,
+    Bounce,
+    Elastic,
+}
+
+impl EaseFunction {
+    fn ease_in(self, t: f64) -> f64 {
+        match self {
+            Self::Quad => t.powi(2),
+            Self::Cubic => t.powi(3),
+            Self::Quart => t.powi(4),
+            Self::Quint => t.powi(5),
+            Self::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Self::Circ => 1.0 - (1.0 - t * t).max(0.0).sqrt(),
+            Self::Back => {
+                const OVERSHOOT: f64 = 1.70158;
+                t * t * ((OVERSHOOT + 1.0) * t - OVERSHOOT)
+            }
+            Self::Bounce => 1.0 - bounce_out(1.0 - t),
+            Self::Elastic => {
+                if t <= 0.0 || t >= 1.0 {
+                    return t;
+                }
+                const PERIOD: f64 = 0.3;
+                let t = t - 1.0;
+                -(2.0f64.powf(10.0 * t)) * ((t - PERIOD / 4.0) * (2.0 * PI) / PERIOD).sin()
+            }
+        }
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    if t < 1.0 / 2.75 {
+        7.5625 * t * t
+    } else if t < 2.0 / 2.75 {
+        let t = t - 1.5 / 2.75;
+        7.5625 * t * t + 0.75
+    } else if t < 2.5 / 2.75 {
+        let t = t - 2.25 / 2.75;
+        7.5625 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / 2.75;
+        7.5625 * t * t + 0.984375
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EaseDirection {
+    In,
+    Out,
+    InOut,
+}
+
+/// Get the progress at a time on a chain of cubic Bézier curves.
+pub(crate) fn evaluate_bezier(points: &[(f64, f64)], t: f64) -> f64 {
+    let num_curves = points.len().saturating_sub(1) / 3;
+    if num_curves == 0 {
+        return t;
+    }
+    if t <= points[0].0 {
+        return points[0].1;
+    }
+    if t >= points[num_curves * 3].0 {
+        return points[num_curves * 3].1;
+    }
+
+    for i in 0..num_curves {
+        let (p0, p1, p2, p3) = (
+            points[i * 3],
+            points[i * 3 + 1],
+            points[i * 3 + 2],
+            points[i * 3 + 3],
+        );
+        if t > p3.0 && i + 1 < num_curves {
+            continue;
+        }
+
+        // X increases along the curve, so find the parameter at this time by bisection.
+        let mut low = 0.0;
+        let mut high = 1.0;
+        for _ in 0..32 {
+            let middle = (low + high) / 2.0;
+            if cubic(p0.0, p1.0, p2.0, p3.0, middle) < t {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        return cubic(p0.1, p1.1, p2.1, p3.1, (low + high) / 2.0);
+    }
+
+    t
+}
+
+fn cubic(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let u = 1.0 - t;
+    u * u * u * p0 + 3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t * p3
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_evaluate_eases() {
+        let tweens: Tweens = quick_xml::de::from_str(
+            r#"<tweens>
+                <CustomEase target="position">
+                    <Point/>
+                    <Point x="0" y="1"/>
+                    <Point x="0" y="1"/>
+                    <Point x="0.5" y="1"/>
+                    <Point x="1" y="1"/>
+                    <Point x="1" y="1"/>
+                    <Point x="1" y="1"/>
+                </CustomEase>
+                <Ease target="rotation" method="quadIn"/>
+                <Ease target="scale" method="sineInOut"/>
+                <Ease target="color" intensity="-100"/>
+            </tweens>"#,
+        )
+        .unwrap();
+
+        let position = tweens.get_curve(EaseTarget::Position).unwrap();
+        assert_eq!(position.evaluate(0.0), 0.0);
+        assert!((position.evaluate(0.5) - 1.0).abs() < 1e-6);
+        assert!((position.evaluate(0.75) - 1.0).abs() < 1e-6);
+
+        let rotation = tweens.get_curve(EaseTarget::Rotation).unwrap();
+        assert_eq!(
+            rotation,
+            EaseCurve::Preset(EaseFunction::Quad, EaseDirection::In)
+        );
+        assert_eq!(rotation.evaluate(0.5), 0.25);
+
+        let scale = tweens.get_curve(EaseTarget::Scale).unwrap();
+        assert!((scale.evaluate(0.5) - 0.5).abs() < 1e-9);
+        assert!(scale.evaluate(0.25) < 0.25);
+
+        assert_eq!(
+            tweens.get_curve(EaseTarget::Color).unwrap().evaluate(0.5),
+            0.25
+        );
+        assert!(tweens.get_curve(EaseTarget::Filters).is_none());
+    }
+}
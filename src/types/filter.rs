@@ -0,0 +1,508 @@
+use crate::types::{
+    dom_shape::deserialize_rgb,
+    lerp,
+    lerp_rgb,
+};
+
+/// The `<filters>` wrapper element.
+#[derive(Debug, serde::Deserialize)]
+pub struct Filters {
+    #[serde(rename = "$value", default)]
+    pub filters: Vec<Filter>,
+}
+
+impl Filters {
+    /// Get the filters, skipping ones that are not supported.
+    pub fn get_filters(&self) -> impl Iterator<Item = &Filter> {
+        self.filters
+            .iter()
+            .filter(|filter| !matches!(filter, Filter::Unknown))
+    }
+}
+
+/// A filter applied to the rasterized content of an instance.
+///
+/// Blur sizes and distances are in pixels. They are not affected by the matrix of the instance, like the Flash Player.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub enum Filter {
+    #[serde(rename = "BlurFilter")]
+    Blur(BlurFilter),
+
+    #[serde(rename = "DropShadowFilter")]
+    DropShadow(DropShadowFilter),
+
+    #[serde(rename = "GlowFilter")]
+    Glow(GlowFilter),
+
+    #[serde(rename = "BevelFilter")]
+    Bevel(BevelFilter),
+
+    #[serde(rename = "GradientGlowFilter")]
+    GradientGlow(GradientGlowFilter),
+
+    #[serde(rename = "AdjustColorFilter")]
+    AdjustColor(AdjustColorFilter),
+
+    #[serde(other)]
+    Unknown,
+}
+
+impl Filter {
+    /// Get how far this filter may draw outside of the content it is applied to, horizontally and vertically, in pixels.
+    ///
+    /// Blurs spread by half their size for each pass, and shadows may be offset by their distance in any direction.
+    /// Inner shadows, glows and bevels stay inside the content.
+    pub fn get_extent(&self) -> (f64, f64) {
+        let blur = |blur_x: f64, blur_y: f64, quality: u64| {
+            let passes = quality.max(1) as f64;
+            (blur_x / 2.0 * passes, blur_y / 2.0 * passes)
+        };
+        let ((x, y), distance) = match self {
+            Self::Blur(filter) => (blur(filter.blur_x, filter.blur_y, filter.quality), 0.0),
+            Self::DropShadow(filter) if !filter.inner => (
+                blur(filter.blur_x, filter.blur_y, filter.quality),
+                filter.distance,
+            ),
+            Self::Glow(filter) if !filter.inner => {
+                (blur(filter.blur_x, filter.blur_y, filter.quality), 0.0)
+            }
+            Self::Bevel(filter)
+                if filter.filter_type.unwrap_or(FilterType::Inner) != FilterType::Inner =>
+            {
+                (
+                    blur(filter.blur_x, filter.blur_y, filter.quality),
+                    filter.distance,
+                )
+            }
+            Self::GradientGlow(filter) if filter.filter_type != Some(FilterType::Inner) => (
+                blur(filter.blur_x, filter.blur_y, filter.quality),
+                filter.distance,
+            ),
+            _ => return (0.0, 0.0),
+        };
+
+        (x + distance.abs(), y + distance.abs())
+    }
+
+    /// Interpolate between this filter, at `t` = 0, and `other`, at `t` = 1.
+    ///
+    /// Filters of different kinds can not be interpolated, so this filter is kept.
+    /// Flags, qualities and types are taken from this filter.
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Self::Blur(start), Self::Blur(end)) => Self::Blur(BlurFilter {
+                blur_x: lerp(start.blur_x, end.blur_x, t),
+                blur_y: lerp(start.blur_y, end.blur_y, t),
+                ..start.clone()
+            }),
+            (Self::DropShadow(start), Self::DropShadow(end)) => {
+                Self::DropShadow(DropShadowFilter {
+                    blur_x: lerp(start.blur_x, end.blur_x, t),
+                    blur_y: lerp(start.blur_y, end.blur_y, t),
+                    strength: lerp(start.strength, end.strength, t),
+                    angle: lerp(start.angle, end.angle, t),
+                    distance: lerp(start.distance, end.distance, t),
+                    color: lerp_rgb(start.color, end.color, t),
+                    alpha: lerp(start.alpha, end.alpha, t),
+                    ..start.clone()
+                })
+            }
+            (Self::Glow(start), Self::Glow(end)) => Self::Glow(GlowFilter {
+                blur_x: lerp(start.blur_x, end.blur_x, t),
+                blur_y: lerp(start.blur_y, end.blur_y, t),
+                strength: lerp(start.strength, end.strength, t),
+                color: lerp_rgb(start.color, end.color, t),
+                alpha: lerp(start.alpha, end.alpha, t),
+                ..start.clone()
+            }),
+            (Self::Bevel(start), Self::Bevel(end)) => Self::Bevel(BevelFilter {
+                blur_x: lerp(start.blur_x, end.blur_x, t),
+                blur_y: lerp(start.blur_y, end.blur_y, t),
+                strength: lerp(start.strength, end.strength, t),
+                angle: lerp(start.angle, end.angle, t),
+                distance: lerp(start.distance, end.distance, t),
+                highlight_color: lerp_rgb(start.highlight_color, end.highlight_color, t),
+                highlight_alpha: lerp(start.highlight_alpha, end.highlight_alpha, t),
+                shadow_color: lerp_rgb(start.shadow_color, end.shadow_color, t),
+                shadow_alpha: lerp(start.shadow_alpha, end.shadow_alpha, t),
+                ..start.clone()
+            }),
+            (Self::GradientGlow(start), Self::GradientGlow(end)) => {
+                // Gradients with different stops keep the stops of this filter.
+                let gradient_entries = if start.gradient_entries.len() == end.gradient_entries.len()
+                {
+                    start
+                        .gradient_entries
+                        .iter()
+                        .zip(end.gradient_entries.iter())
+                        .map(|(start, end)| GradientEntry {
+                            color: lerp_rgb(start.color, end.color, t),
+                            alpha: lerp(start.alpha, end.alpha, t),
+                            ratio: lerp(start.ratio, end.ratio, t),
+                        })
+                        .collect()
+                } else {
+                    start.gradient_entries.clone()
+                };
+
+                Self::GradientGlow(GradientGlowFilter {
+                    blur_x: lerp(start.blur_x, end.blur_x, t),
+                    blur_y: lerp(start.blur_y, end.blur_y, t),
+                    strength: lerp(start.strength, end.strength, t),
+                    angle: lerp(start.angle, end.angle, t),
+                    distance: lerp(start.distance, end.distance, t),
+                    gradient_entries,
+                    ..start.clone()
+                })
+            }
+            (Self::AdjustColor(start), Self::AdjustColor(end)) => {
+                Self::AdjustColor(AdjustColorFilter {
+                    brightness: lerp(start.brightness, end.brightness, t),
+                    contrast: lerp(start.contrast, end.contrast, t),
+                    saturation: lerp(start.saturation, end.saturation, t),
+                    hue: lerp(start.hue, end.hue, t),
+                })
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Set a numeric attribute of this filter by its name in the XFL, like `blurX`.
+    ///
+    /// Returns `false` if this filter has no such attribute.
+    pub fn set_property(&mut self, name: &str, value: f64) -> bool {
+        let field = match (self, name) {
+            (Self::Blur(filter), "blurX") => &mut filter.blur_x,
+            (Self::Blur(filter), "blurY") => &mut filter.blur_y,
+            (Self::DropShadow(filter), "blurX") => &mut filter.blur_x,
+            (Self::DropShadow(filter), "blurY") => &mut filter.blur_y,
+            (Self::DropShadow(filter), "strength") => &mut filter.strength,
+            (Self::DropShadow(filter), "angle") => &mut filter.angle,
+            (Self::DropShadow(filter), "distance") => &mut filter.distance,
+            (Self::DropShadow(filter), "alpha") => &mut filter.alpha,
+            (Self::Glow(filter), "blurX") => &mut filter.blur_x,
+            (Self::Glow(filter), "blurY") => &mut filter.blur_y,
+            (Self::Glow(filter), "strength") => &mut filter.strength,
+            (Self::Glow(filter), "alpha") => &mut filter.alpha,
+            (Self::Bevel(filter), "blurX") => &mut filter.blur_x,
+            (Self::Bevel(filter), "blurY") => &mut filter.blur_y,
+            (Self::Bevel(filter), "strength") => &mut filter.strength,
+            (Self::Bevel(filter), "angle") => &mut filter.angle,
+            (Self::Bevel(filter), "distance") => &mut filter.distance,
+            (Self::Bevel(filter), "highlightAlpha") => &mut filter.highlight_alpha,
+            (Self::Bevel(filter), "shadowAlpha") => &mut filter.shadow_alpha,
+            (Self::GradientGlow(filter), "blurX") => &mut filter.blur_x,
+            (Self::GradientGlow(filter), "blurY") => &mut filter.blur_y,
+            (Self::GradientGlow(filter), "strength") => &mut filter.strength,
+            (Self::GradientGlow(filter), "angle") => &mut filter.angle,
+            (Self::GradientGlow(filter), "distance") => &mut filter.distance,
+            (Self::AdjustColor(filter), "brightness") => &mut filter.brightness,
+            (Self::AdjustColor(filter), "contrast") => &mut filter.contrast,
+            (Self::AdjustColor(filter), "saturation") => &mut filter.saturation,
+            (Self::AdjustColor(filter), "hue") => &mut filter.hue,
+            _ => return false,
+        };
+        *field = value;
+
+        true
+    }
+}
+
+fn default_blur() -> f64 {
+    5.0
+}
+
+fn default_distance() -> f64 {
+    5.0
+}
+
+fn default_angle() -> f64 {
+    45.0
+}
+
+fn default_one() -> f64 {
+    1.0
+}
+
+fn default_quality() -> u64 {
+    1
+}
+
+fn default_black() -> (u8, u8, u8) {
+    (0, 0, 0)
+}
+
+fn default_white() -> (u8, u8, u8) {
+    (0xFF, 0xFF, 0xFF)
+}
+
+fn default_red() -> (u8, u8, u8) {
+    (0xFF, 0, 0)
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BlurFilter {
+    #[serde(rename = "blurX", default = "default_blur")]
+    pub blur_x: f64,
+
+    #[serde(rename = "blurY", default = "default_blur")]
+    pub blur_y: f64,
+
+    /// The number of times the blur is applied.
+    #[serde(default = "default_quality")]
+    pub quality: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DropShadowFilter {
+    #[serde(rename = "blurX", default = "default_blur")]
+    pub blur_x: f64,
+
+    #[serde(rename = "blurY", default = "default_blur")]
+    pub blur_y: f64,
+
+    #[serde(default = "default_quality")]
+    pub quality: u64,
+
+    #[serde(default = "default_one")]
+    pub strength: f64,
+
+    /// The direction of the shadow, in degrees.
+    #[serde(default = "default_angle")]
+    pub angle: f64,
+
+    #[serde(default = "default_distance")]
+    pub distance: f64,
+
+    #[serde(default = "default_black", deserialize_with = "deserialize_rgb")]
+    pub color: (u8, u8, u8),
+
+    #[serde(default = "default_one")]
+    pub alpha: f64,
+
+    #[serde(default)]
+    pub inner: bool,
+
+    #[serde(default)]
+    pub knockout: bool,
+
+    #[serde(rename = "hideObject", default)]
+    pub hide_object: bool,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GlowFilter {
+    #[serde(rename = "blurX", default = "default_blur")]
+    pub blur_x: f64,
+
+    #[serde(rename = "blurY", default = "default_blur")]
+    pub blur_y: f64,
+
+    #[serde(default = "default_quality")]
+    pub quality: u64,
+
+    #[serde(default = "default_one")]
+    pub strength: f64,
+
+    #[serde(default = "default_red", deserialize_with = "deserialize_rgb")]
+    pub color: (u8, u8, u8),
+
+    #[serde(default = "default_one")]
+    pub alpha: f64,
+
+    #[serde(default)]
+    pub inner: bool,
+
+    #[serde(default)]
+    pub knockout: bool,
+}
+
+/// Where a bevel or gradient filter is drawn, relative to the edges of the object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum FilterType {
+    #[serde(rename = "inner")]
+    Inner,
+
+    #[serde(rename = "outer")]
+    Outer,
+
+    #[serde(rename = "full")]
+    Full,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BevelFilter {
+    #[serde(rename = "blurX", default = "default_blur")]
+    pub blur_x: f64,
+
+    #[serde(rename = "blurY", default = "default_blur")]
+    pub blur_y: f64,
+
+    #[serde(default = "default_quality")]
+    pub quality: u64,
+
+    #[serde(default = "default_one")]
+    pub strength: f64,
+
+    #[serde(default = "default_angle")]
+    pub angle: f64,
+
+    #[serde(default = "default_distance")]
+    pub distance: f64,
+
+    #[serde(
+        rename = "highlightColor",
+        default = "default_white",
+        deserialize_with = "deserialize_rgb"
+    )]
+    pub highlight_color: (u8, u8, u8),
+
+    #[serde(rename = "highlightAlpha", default = "default_one")]
+    pub highlight_alpha: f64,
+
+    #[serde(
+        rename = "shadowColor",
+        default = "default_black",
+        deserialize_with = "deserialize_rgb"
+    )]
+    pub shadow_color: (u8, u8, u8),
+
+    #[serde(rename = "shadowAlpha", default = "default_one")]
+    pub shadow_alpha: f64,
+
+    #[serde(default)]
+    pub knockout: bool,
+
+    #[serde(rename = "type")]
+    pub filter_type: Option<FilterType>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GradientGlowFilter {
+    #[serde(rename = "blurX", default = "default_blur")]
+    pub blur_x: f64,
+
+    #[serde(rename = "blurY", default = "default_blur")]
+    pub blur_y: f64,
+
+    #[serde(default = "default_quality")]
+    pub quality: u64,
+
+    #[serde(default = "default_one")]
+    pub strength: f64,
+
+    #[serde(default = "default_angle")]
+    pub angle: f64,
+
+    #[serde(default = "default_distance")]
+    pub distance: f64,
+
+    #[serde(default)]
+    pub knockout: bool,
+
+    #[serde(rename = "type")]
+    pub filter_type: Option<FilterType>,
+
+    #[serde(rename = "GradientEntry", default)]
+    pub gradient_entries: Vec<GradientEntry>,
+}
+
+/// A color stop of a gradient filter.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GradientEntry {
+    #[serde(default = "default_black", deserialize_with = "deserialize_rgb")]
+    pub color: (u8, u8, u8),
+
+    #[serde(default = "default_one")]
+    pub alpha: f64,
+
+    /// The position of this entry, from 0 to 1.
+    #[serde(default)]
+    pub ratio: f64,
+}
+
+/// Adjusts the color of an object. Each property ranges from -100 to 100, except hue, which ranges from -180 to 180.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AdjustColorFilter {
+    #[serde(default)]
+    pub brightness: f64,
+
+    #[serde(default)]
+    pub contrast: f64,
+
+    #[serde(default)]
+    pub saturation: f64,
+
+    #[serde(default)]
+    pub hue: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FILTERS: &str = r##"<filters>
+    <DropShadowFilter color="#333333" distance="3" knockout="true"/>
+    <UnknownFilter/>
+    <GradientGlowFilter type="outer" quality="3">
+        <GradientEntry color="#FFFFFF" alpha="0" ratio="0"/>
+        <GradientEntry color="#FF0000" ratio="1"/>
+    </GradientGlowFilter>
+    <AdjustColorFilter saturation="-100"/>
+</filters>"##;
+
+    #[test]
+    fn parse_filters() {
+        let filters: Filters = quick_xml::de::from_str(FILTERS).unwrap();
+        let filters: Vec<_> = filters.get_filters().collect();
+        assert_eq!(filters.len(), 3);
+
+        match filters[0] {
+            Filter::DropShadow(filter) => {
+                assert_eq!(filter.distance, 3.0);
+                assert_eq!(filter.blur_x, 5.0);
+                assert!(filter.knockout);
+            }
+            filter => panic!("unexpected filter {:?}", filter),
+        }
+
+        match filters[1] {
+            Filter::GradientGlow(filter) => {
+                assert_eq!(filter.quality, 3);
+                assert_eq!(filter.gradient_entries.len(), 2);
+                assert_eq!(filter.gradient_entries[1].color, (0xFF, 0, 0));
+            }
+            filter => panic!("unexpected filter {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn filter_extents() {
+        let filters: Filters = quick_xml::de::from_str(FILTERS).unwrap();
+        let extents: Vec<_> = filters.get_filters().map(Filter::get_extent).collect();
+        assert_eq!(extents, [(5.5, 5.5), (12.5, 12.5), (0.0, 0.0)]);
+
+        let inner: Filters =
+            quick_xml::de::from_str(r#"<filters><GlowFilter inner="true"/></filters>"#).unwrap();
+        assert_eq!(inner.filters[0].get_extent(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn interpolate_colors() {
+        let start: Filters =
+            quick_xml::de::from_str(r##"<filters><GlowFilter color="#FF0000"/></filters>"##)
+                .unwrap();
+        let end: Filters = quick_xml::de::from_str(
+            r##"<filters><GlowFilter color="#0000FF" alpha="0"/></filters>"##,
+        )
+        .unwrap();
+
+        match start.filters[0].interpolate(&end.filters[0], 0.5) {
+            Filter::Glow(filter) => {
+                assert_eq!(filter.color, (0x80, 0, 0x80));
+                assert_eq!(filter.alpha, 0.5);
+            }
+            filter => panic!("unexpected filter {:?}", filter),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+/// The number of twips in a pixel.
+///
+/// Edge coordinates are stored in twips, while matrix translations are stored in pixels.
+pub const TWIPS_PER_PIXEL: f64 = 20.0;
+
+/// A 2D affine transform.
+///
+/// A point (x, y) is mapped to (a * x + c * y + tx, b * x + d * y + ty).
+/// Missing attributes take the identity values from the XFL spec.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct Matrix {
+    #[serde(default = "one")]
+    pub a: f64,
+
+    #[serde(default)]
+    pub b: f64,
+
+    #[serde(default)]
+    pub c: f64,
+
+    #[serde(default = "one")]
+    pub d: f64,
+
+    #[serde(default)]
+    pub tx: f64,
+
+    #[serde(default)]
+    pub ty: f64,
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+impl Matrix {
+    /// The identity matrix.
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    /// Make a new matrix.
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, tx: f64, ty: f64) -> Self {
+        Self { a, b, c, d, tx, ty }
+    }
+
+    /// Make a translation matrix.
+    pub const fn translation(tx: f64, ty: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, tx, ty)
+    }
+
+    /// Make a scale matrix.
+    pub const fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// Make a matrix from a euclid transform.
+    pub fn from_transform(transform: &euclid::default::Transform2D<f64>) -> Self {
+        Self::new(
+            transform.m11,
+            transform.m12,
+            transform.m21,
+            transform.m22,
+            transform.m31,
+            transform.m32,
+        )
+    }
+
+    /// Convert this matrix into a euclid transform, in pixels.
+    pub fn to_transform(&self) -> euclid::default::Transform2D<f64> {
+        euclid::default::Transform2D::new(self.a, self.b, self.c, self.d, self.tx, self.ty)
+    }
+
+    /// Convert this matrix into a euclid transform, in twips.
+    ///
+    /// This is the transform that should be applied to edge coordinates.
+    pub fn to_twips_transform(&self) -> euclid::default::Transform2D<f64> {
+        euclid::default::Transform2D::new(
+            self.a,
+            self.b,
+            self.c,
+            self.d,
+            self.tx * TWIPS_PER_PIXEL,
+            self.ty * TWIPS_PER_PIXEL,
+        )
+    }
+
+    /// Get a matrix that applies `self`, then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self::from_transform(&self.to_transform().then(&other.to_transform()))
+    }
+
+    /// Get a matrix that applies `other`, then `self`.
+    pub fn pre_then(&self, other: &Self) -> Self {
+        other.then(self)
+    }
+
+    /// Get the inverse of this matrix, if it exists.
+    pub fn inverse(&self) -> Option<Self> {
+        Some(Self::from_transform(&self.to_transform().inverse()?))
+    }
+
+    /// Check if this is the identity matrix.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Transform a point, in pixels.
+    pub fn transform_point(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+
+    /// Get the smallest box containing the transformed box. Both boxes are in twips.
+    pub fn transform_twips_box(
+        &self,
+        bounding_box: &euclid::Box2D<f64, euclid::UnknownUnit>,
+    ) -> euclid::Box2D<f64, euclid::UnknownUnit> {
+        self.to_twips_transform()
+            .outer_transformed_box(bounding_box)
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<Matrix> for euclid::default::Transform2D<f64> {
+    fn from(matrix: Matrix) -> Self {
+        matrix.to_transform()
+    }
+}
+
+impl From<euclid::default::Transform2D<f64>> for Matrix {
+    fn from(transform: euclid::default::Transform2D<f64>) -> Self {
+        Self::from_transform(&transform)
+    }
+}
+
+/// The `<matrix>` wrapper element.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Matrices {
+    #[serde(rename = "Matrix", default)]
+    pub matrix: Vec<Matrix>,
+}
+
+impl Matrices {
+    /// Get the matrix, or the identity if it is missing.
+    pub fn get_matrix(&self) -> Matrix {
+        self.matrix.first().copied().unwrap_or_default()
+    }
+}
+
+/// A 2D point, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize)]
+pub struct Point {
+    #[serde(default)]
+    pub x: f64,
+
+    #[serde(default)]
+    pub y: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_defaults() {
+        let matrix: Matrix = quick_xml::de::from_str(r#"<Matrix b="0.5" tx="10"/>"#).unwrap();
+        assert_eq!(matrix, Matrix::new(1.0, 0.5, 0.0, 1.0, 10.0, 0.0));
+
+        let matrix: Matrix = quick_xml::de::from_str("<Matrix/>").unwrap();
+        assert!(matrix.is_identity());
+    }
+
+    #[test]
+    fn compose() {
+        let scale = Matrix::scale(2.0, 3.0);
+        let translate = Matrix::translation(5.0, 7.0);
+
+        assert_eq!(
+            scale.then(&translate).transform_point(1.0, 1.0),
+            (7.0, 10.0)
+        );
+        assert_eq!(
+            scale.pre_then(&translate).transform_point(1.0, 1.0),
+            (12.0, 24.0)
+        );
+
+        let inverse = scale.then(&translate).inverse().unwrap();
+        assert_eq!(inverse.transform_point(7.0, 10.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn euclid_round_trip() {
+        let matrix = Matrix::new(0.0, 1.0, -1.0, 0.0, 3.0, 4.0);
+        let transform = matrix.to_transform();
+        let point = transform.transform_point(euclid::point2(1.0, 0.0));
+        assert_eq!((point.x, point.y), matrix.transform_point(1.0, 0.0));
+        assert_eq!(Matrix::from(transform), matrix);
+    }
+}
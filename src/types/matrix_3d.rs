@@ -0,0 +1,202 @@
+use crate::types::{
+    matrix::TWIPS_PER_PIXEL,
+    Matrix,
+};
+
+/// A 3D affine transform, as stored by the 3D rotation and translation tools.
+///
+/// Like the `flash.geom.Matrix3D` class, a point (x, y, z) is mapped to
+/// (x * m00 + y * m10 + z * m20 + m30, x * m01 + y * m11 + z * m21 + m31, x * m02 + y * m12 + z * m22 + m32).
+/// Translations are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct Matrix3D {
+    #[serde(default = "one")]
+    pub m00: f64,
+    #[serde(default)]
+    pub m01: f64,
+    #[serde(default)]
+    pub m02: f64,
+    #[serde(default)]
+    pub m03: f64,
+
+    #[serde(default)]
+    pub m10: f64,
+    #[serde(default = "one")]
+    pub m11: f64,
+    #[serde(default)]
+    pub m12: f64,
+    #[serde(default)]
+    pub m13: f64,
+
+    #[serde(default)]
+    pub m20: f64,
+    #[serde(default)]
+    pub m21: f64,
+    #[serde(default = "one")]
+    pub m22: f64,
+    #[serde(default)]
+    pub m23: f64,
+
+    #[serde(default)]
+    pub m30: f64,
+    #[serde(default)]
+    pub m31: f64,
+    #[serde(default)]
+    pub m32: f64,
+    #[serde(default = "one")]
+    pub m33: f64,
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+impl Matrix3D {
+    /// The identity matrix.
+    pub const IDENTITY: Self = Self {
+        m00: 1.0,
+        m01: 0.0,
+        m02: 0.0,
+        m03: 0.0,
+        m10: 0.0,
+        m11: 1.0,
+        m12: 0.0,
+        m13: 0.0,
+        m20: 0.0,
+        m21: 0.0,
+        m22: 1.0,
+        m23: 0.0,
+        m30: 0.0,
+        m31: 0.0,
+        m32: 0.0,
+        m33: 1.0,
+    };
+
+    /// Transform a point, in pixels.
+    pub fn transform_point(&self, x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+        (
+            x * self.m00 + y * self.m10 + z * self.m20 + self.m30,
+            x * self.m01 + y * self.m11 + z * self.m21 + self.m31,
+            x * self.m02 + y * self.m12 + z * self.m22 + self.m32,
+        )
+    }
+
+    /// Transform a bounding box in twips, and project it with the given perspective.
+    pub fn project_twips_box(
+        &self,
+        bounding_box: &euclid::Box2D<f64, euclid::UnknownUnit>,
+        perspective: &Perspective,
+    ) -> euclid::Box2D<f64, euclid::UnknownUnit> {
+        let corners = [
+            bounding_box.min,
+            euclid::point2(bounding_box.max.x, bounding_box.min.y),
+            bounding_box.max,
+            euclid::point2(bounding_box.min.x, bounding_box.max.y),
+        ];
+
+        euclid::Box2D::from_points(corners.iter().filter_map(|corner| {
+            let (x, y, z) =
+                self.transform_point(corner.x / TWIPS_PER_PIXEL, corner.y / TWIPS_PER_PIXEL, 0.0);
+            let (x, y) = perspective.project(x, y, z)?;
+            Some(euclid::point2(x * TWIPS_PER_PIXEL, y * TWIPS_PER_PIXEL))
+        }))
+    }
+
+    /// Get the 2D part of this matrix, ignoring depth.
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::new(self.m00, self.m01, self.m10, self.m11, self.m30, self.m31)
+    }
+}
+
+impl Default for Matrix3D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// The `<matrix3D>` wrapper element.
+#[derive(Debug, serde::Deserialize)]
+pub struct Matrices3D {
+    #[serde(rename = "Matrix3D", default)]
+    pub matrix: Vec<Matrix3D>,
+}
+
+impl Matrices3D {
+    pub fn get_matrix(&self) -> Option<Matrix3D> {
+        self.matrix.first().copied()
+    }
+}
+
+/// A perspective projection, like the `flash.geom.PerspectiveProjection` class.
+///
+/// Points further away, with a larger z, are drawn closer to the projection center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perspective {
+    /// The distance from the viewer to the plane where z is 0, in pixels.
+    pub focal_length: f64,
+
+    /// The vanishing point, in pixels.
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+impl Perspective {
+    /// Make a projection for a stage, from its size in pixels and its field of view in degrees.
+    ///
+    /// The projection is centered on the stage.
+    pub fn new(width: f64, height: f64, view_angle: f64) -> Self {
+        let view_angle = view_angle.clamp(1.0, 179.0).to_radians();
+        Self {
+            focal_length: (width / 2.0) / (view_angle / 2.0).tan(),
+            center_x: width / 2.0,
+            center_y: height / 2.0,
+        }
+    }
+
+    /// Project a point, in pixels.
+    ///
+    /// Returns `None` if the point is behind the viewer.
+    pub fn project(&self, x: f64, y: f64, z: f64) -> Option<(f64, f64)> {
+        let depth = self.focal_length + z;
+        if depth <= 0.0 {
+            return None;
+        }
+
+        let scale = self.focal_length / depth;
+        Some((
+            self.center_x + (x - self.center_x) * scale,
+            self.center_y + (y - self.center_y) * scale,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate_and_project() {
+        // A rotation of 90 degrees around the y axis
+        let matrix: Matrix3D =
+            quick_xml::de::from_str(r#"<Matrix3D m00="0" m02="-1" m20="1" m22="0" m30="50"/>"#)
+                .unwrap();
+        assert_eq!(matrix.transform_point(10.0, 5.0, 0.0), (50.0, 5.0, -10.0));
+        assert_eq!(
+            matrix.to_matrix(),
+            Matrix::new(0.0, 0.0, 0.0, 1.0, 50.0, 0.0)
+        );
+
+        let perspective = Perspective::new(100.0, 100.0, 90.0);
+        assert!((perspective.focal_length - 50.0).abs() < 1e-9);
+        assert_eq!((perspective.center_x, perspective.center_y), (50.0, 50.0));
+
+        let perspective = Perspective {
+            focal_length: 50.0,
+            ..perspective
+        };
+        assert_eq!(perspective.project(50.0, 50.0, 100.0), Some((50.0, 50.0)));
+        assert_eq!(perspective.project(100.0, 50.0, 50.0), Some((75.0, 50.0)));
+        assert_eq!(perspective.project(0.0, 50.0, -25.0), Some((-50.0, 50.0)));
+        assert_eq!(perspective.project(0.0, 0.0, -50.0), None);
+    }
+}
@@ -0,0 +1,479 @@
+use crate::types::{
+    color_transform::ColorTransform,
+    ease::{
+        evaluate_bezier,
+        EaseCurve,
+    },
+    Filter,
+};
+
+/// The `<motionObjectXML>` element of a motion tween, holding its property curves.
+#[derive(Debug, serde::Deserialize)]
+pub struct MotionObjectXml {
+    #[serde(rename = "AnimationCore")]
+    pub animation_core: Option<AnimationCore>,
+}
+
+/// The property curves of a motion tween.
+///
+/// Times are in `time_scale` units per second.
+#[derive(Debug, serde::Deserialize)]
+pub struct AnimationCore {
+    #[serde(rename = "TimeScale", default = "default_time_scale")]
+    pub time_scale: u64,
+
+    /// The length of the tween, in `time_scale` units.
+    pub duration: Option<u64>,
+
+    /// The ease applied to the time of every property that does not ignore it.
+    #[serde(rename = "TimeMap")]
+    pub time_map: Option<TimeMap>,
+
+    #[serde(rename = "PropertyContainer", default)]
+    pub property_containers: Vec<PropertyContainer>,
+}
+
+fn default_time_scale() -> u64 {
+    24000
+}
+
+impl AnimationCore {
+    /// Get the time, in `time_scale` units, `elapsed` frames into a tween lasting `duration` frames.
+    ///
+    /// Tweens without a duration are timed by the frame rate of the document, in frames per second.
+    /// The time map is not applied.
+    pub fn get_time(&self, elapsed: usize, duration: usize, frame_rate: f64) -> f64 {
+        let units_per_frame = match self.duration {
+            Some(total) if total > 0 => total as f64 / duration.max(1) as f64,
+            _ if frame_rate > 0.0 => self.time_scale as f64 / frame_rate,
+            _ => self.time_scale as f64,
+        };
+
+        elapsed as f64 * units_per_frame
+    }
+
+    /// Find a property by id, searching nested containers.
+    pub fn get_property(&self, id: &str) -> Option<&Property> {
+        self.property_containers
+            .iter()
+            .find_map(|container| container.get_property(id))
+    }
+
+    /// Find a container by id, searching nested containers.
+    pub fn get_container(&self, id: &str) -> Option<&PropertyContainer> {
+        self.property_containers
+            .iter()
+            .find_map(|container| container.get_container(id))
+    }
+
+    /// Evaluate a property at a time, in `time_scale` units, applying the time map unless the property ignores it.
+    pub fn evaluate(&self, property: &Property, time: f64) -> Option<f64> {
+        let time = match (&self.time_map, self.duration) {
+            (Some(time_map), Some(duration)) if duration > 0 && !property.ignores_time_map() => {
+                let duration = duration as f64;
+                time_map.get_curve().evaluate(time / duration) * duration
+            }
+            _ => time,
+        };
+
+        property.evaluate(time)
+    }
+
+    /// Evaluate a property by id. Missing and disabled properties have no value.
+    pub fn evaluate_id(&self, id: &str, time: f64) -> Option<f64> {
+        self.get_property(id)
+            .filter(|property| property.is_enabled())
+            .and_then(|property| self.evaluate(property, time))
+    }
+
+    /// Get the transform of a motion tween at a time, relative to the instance at its first frame.
+    pub fn get_transform(&self, time: f64) -> MotionTransform {
+        let get = |id, default| self.evaluate_id(id, time).unwrap_or(default);
+
+        MotionTransform {
+            x: get("Motion_X", 0.0),
+            y: get("Motion_Y", 0.0),
+            rotation: get("Rotation_Z", 0.0),
+            skew_x: get("Skew_X", 0.0),
+            skew_y: get("Skew_Y", 0.0),
+            scale_x: get("Scale_X", 100.0) / 100.0,
+            scale_y: get("Scale_Y", 100.0) / 100.0,
+        }
+    }
+
+    /// Get the offsets of the transformation point, in pixels, at each keyframe of the motion.
+    pub fn get_keyframe_offsets(&self) -> Vec<(f64, f64)> {
+        ["Motion_X", "Motion_Y"]
+            .iter()
+            .filter_map(|id| self.get_property(id))
+            .flat_map(|property| property.keyframes.iter())
+            .map(|keyframe| {
+                let transform = self.get_transform(keyframe.time_value as f64);
+                (transform.x, transform.y)
+            })
+            .collect()
+    }
+
+    /// Get the color effect of a motion tween at a time, if it animates one.
+    ///
+    /// Amounts and multipliers are percentages, like in the editor.
+    pub fn get_color_transform(&self, time: f64) -> Option<ColorTransform> {
+        let get = |id| self.evaluate_id(id, time);
+
+        if let Some(alpha) = get("Alpha_Amount") {
+            let mut ret = ColorTransform::IDENTITY;
+            ret.multipliers[3] = alpha / 100.0;
+            return Some(ret);
+        }
+
+        if let Some(brightness) = get("Brightness_Amount") {
+            let brightness = (brightness / 100.0).clamp(-1.0, 1.0);
+            let mut ret = ColorTransform::IDENTITY;
+            for i in 0..3 {
+                ret.multipliers[i] = 1.0 - brightness.abs();
+                ret.offsets[i] = brightness.max(0.0) * 255.0;
+            }
+            return Some(ret);
+        }
+
+        if let Some(amount) = get("Tint_Amount") {
+            let amount = (amount / 100.0).clamp(0.0, 1.0);
+            let color = get("Tint_Color").unwrap_or(0.0).round().max(0.0) as u32;
+            let mut ret = ColorTransform::IDENTITY;
+            for i in 0..3 {
+                let channel = (color >> (16 - i * 8)) & 0xFF;
+                ret.multipliers[i] = 1.0 - amount;
+                ret.offsets[i] = f64::from(channel) * amount;
+            }
+            return Some(ret);
+        }
+
+        // A container whose properties are all disabled does not change the color.
+        let advanced = self.get_container("Advanced_Color")?;
+        if !advanced
+            .get_properties()
+            .any(|property| property.is_enabled())
+        {
+            return None;
+        }
+        let get = |id, default| {
+            advanced
+                .get_property(id)
+                .filter(|property| property.is_enabled())
+                .and_then(|property| self.evaluate(property, time))
+                .unwrap_or(default)
+        };
+        Some(ColorTransform {
+            multipliers: [
+                get("Red_Multiplier", 100.0) / 100.0,
+                get("Green_Multiplier", 100.0) / 100.0,
+                get("Blue_Multiplier", 100.0) / 100.0,
+                get("Alpha_Multiplier", 100.0) / 100.0,
+            ],
+            offsets: [
+                get("Red_Offset", 0.0),
+                get("Green_Offset", 0.0),
+                get("Blue_Offset", 0.0),
+                get("Alpha_Offset", 0.0),
+            ],
+        })
+    }
+
+    /// Animate the filters of an instance at a time.
+    ///
+    /// The containers in the `Filters` container are matched to the filters of the instance in order.
+    /// Their properties are named after the attributes of the filters, like `blurX`,
+    /// optionally prefixed by the name of the filter, like `DropShadowFilter_blurX`.
+    pub fn apply_filters(&self, filters: &mut [Filter], time: f64) {
+        let containers = match self.get_container("Filters") {
+            Some(container) => container.get_containers(),
+            None => return,
+        };
+
+        for (filter, container) in filters.iter_mut().zip(containers) {
+            for property in container.get_properties() {
+                if !property.is_enabled() {
+                    continue;
+                }
+                let name = property.id.rsplit('_').next().unwrap_or(&property.id);
+                if let Some(value) = self.evaluate(property, time) {
+                    filter.set_property(name, value);
+                }
+            }
+        }
+    }
+}
+
+/// The transform of a motion tween, relative to the instance at its first frame.
+///
+/// Angles are in degrees and offsets are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionTransform {
+    /// The offset of the transformation point.
+    pub x: f64,
+    pub y: f64,
+
+    pub rotation: f64,
+    pub skew_x: f64,
+    pub skew_y: f64,
+
+    /// Scales, where 1 is the original size.
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+/// The ease of the time of a motion tween.
+#[derive(Debug, serde::Deserialize)]
+pub struct TimeMap {
+    /// The strength of the ease, from -100 (ease in) to 100 (ease out).
+    pub strength: Option<f64>,
+}
+
+impl TimeMap {
+    pub fn get_curve(&self) -> EaseCurve {
+        EaseCurve::Classic(self.strength.unwrap_or(0.0) / 100.0)
+    }
+}
+
+/// A group of properties, like `Basic_Motion` or `Colors`.
+#[derive(Debug, serde::Deserialize)]
+pub struct PropertyContainer {
+    pub id: String,
+
+    #[serde(rename = "$value", default)]
+    pub children: Vec<PropertyContainerChild>,
+}
+
+impl PropertyContainer {
+    pub fn get_containers(&self) -> impl Iterator<Item = &PropertyContainer> {
+        self.children.iter().filter_map(|child| match child {
+            PropertyContainerChild::PropertyContainer(container) => Some(container),
+            _ => None,
+        })
+    }
+
+    pub fn get_properties(&self) -> impl Iterator<Item = &Property> {
+        self.children.iter().filter_map(|child| match child {
+            PropertyContainerChild::Property(property) => Some(property),
+            _ => None,
+        })
+    }
+
+    /// Find a property by id, searching nested containers.
+    pub fn get_property(&self, id: &str) -> Option<&Property> {
+        self.get_properties()
+            .find(|property| property.id == id)
+            .or_else(|| {
+                self.get_containers()
+                    .find_map(|container| container.get_property(id))
+            })
+    }
+
+    /// Find a container by id, which may be this container.
+    pub fn get_container(&self, id: &str) -> Option<&PropertyContainer> {
+        if self.id == id {
+            return Some(self);
+        }
+
+        self.get_containers()
+            .find_map(|container| container.get_container(id))
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub enum PropertyContainerChild {
+    PropertyContainer(PropertyContainer),
+    Property(Property),
+
+    #[serde(other)]
+    Unknown,
+}
+
+/// An animated property, like `Motion_X`.
+#[derive(Debug, serde::Deserialize)]
+pub struct Property {
+    pub id: String,
+
+    pub enabled: Option<u8>,
+
+    #[serde(rename = "ignoreTimeMap")]
+    pub ignore_time_map: Option<u8>,
+
+    #[serde(rename = "Keyframe", default)]
+    pub keyframes: Vec<PropertyKeyframe>,
+}
+
+impl Property {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some(0)
+    }
+
+    pub fn ignores_time_map(&self) -> bool {
+        self.ignore_time_map.unwrap_or(0) != 0
+    }
+
+    /// Get the value at a time, in `time_scale` units.
+    ///
+    /// Values are held before the first keyframe and after the last keyframe.
+    pub fn evaluate(&self, time: f64) -> Option<f64> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        if time <= first.time_value as f64 {
+            return first.get_value();
+        }
+        if time >= last.time_value as f64 {
+            return last.get_value();
+        }
+
+        let end_index = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time_value as f64 > time)?;
+        let start = &self.keyframes[end_index - 1];
+        let end = &self.keyframes[end_index];
+
+        let (t0, v0) = (start.time_value as f64, start.get_value()?);
+        let (t1, v1) = (end.time_value as f64, end.get_value()?);
+        let (next_t, next_v) = start.get_next();
+        let (previous_t, previous_v) = end.get_previous();
+        if (next_t, next_v, previous_t, previous_v) == (0.0, 0.0, 0.0, 0.0) {
+            return Some(v0 + (v1 - v0) * (time - t0) / (t1 - t0));
+        }
+
+        // Clamp the handles, so time increases along the curve.
+        let points = [
+            (t0, v0),
+            ((t0 + next_t).clamp(t0, t1), v0 + next_v),
+            ((t1 + previous_t).clamp(t0, t1), v1 + previous_v),
+            (t1, v1),
+        ];
+        Some(evaluate_bezier(&points, time))
+    }
+}
+
+/// A keyframe of a property.
+///
+/// The anchor is `time, value`, where the time is unused and the value is in the units of the property.
+/// The handles are Bézier control points relative to the anchor, in `time_scale` units and property units.
+#[derive(Debug, serde::Deserialize)]
+pub struct PropertyKeyframe {
+    pub anchor: String,
+    pub next: Option<String>,
+    pub previous: Option<String>,
+
+    #[serde(rename = "timevalue")]
+    pub time_value: u64,
+}
+
+impl PropertyKeyframe {
+    pub fn get_value(&self) -> Option<f64> {
+        parse_pair(&self.anchor).map(|(_, value)| value)
+    }
+
+    pub fn get_next(&self) -> (f64, f64) {
+        self.next
+            .as_deref()
+            .and_then(parse_pair)
+            .unwrap_or_default()
+    }
+
+    pub fn get_previous(&self) -> (f64, f64) {
+        self.previous
+            .as_deref()
+            .and_then(parse_pair)
+            .unwrap_or_default()
+    }
+}
+
+fn parse_pair(value: &str) -> Option<(f64, f64)> {
+    let mut iter = value.split(',').map(|value| value.trim().parse().ok());
+    Some((iter.next()??, iter.next()??))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn evaluate_property_curves() {
+        let motion: MotionObjectXml = quick_xml::de::from_str(
+            r#"<motionObjectXML>
+                <AnimationCore TimeScale="24000" Version="1" duration="10000">
+                    <TimeMap strength="0" type="Quadratic"/>
+                    <metadata/>
+                    <PropertyContainer id="headContainer">
+                        <PropertyContainer id="Basic_Motion">
+                            <Property enabled="1" id="Motion_X" ignoreTimeMap="0">
+                                <Keyframe anchor="0,0" next="0,0" previous="0,0" roving="0" timevalue="0"/>
+                                <Keyframe anchor="0,100" next="0,0" previous="0,0" roving="0" timevalue="10000"/>
+                            </Property>
+                            <Property enabled="1" id="Rotation_Z" ignoreTimeMap="0">
+                                <Keyframe anchor="0,0" next="5000,90" timevalue="0"/>
+                                <Keyframe anchor="0,90" previous="-5000,0" timevalue="10000"/>
+                            </Property>
+                        </PropertyContainer>
+                        <PropertyContainer id="Colors">
+                            <PropertyContainer id="Alpha_Color">
+                                <Property enabled="1" id="Alpha_Amount" ignoreTimeMap="0">
+                                    <Keyframe anchor="0,100" timevalue="0"/>
+                                    <Keyframe anchor="0,0" timevalue="10000"/>
+                                </Property>
+                            </PropertyContainer>
+                        </PropertyContainer>
+                    </PropertyContainer>
+                </AnimationCore>
+            </motionObjectXML>"#,
+        )
+        .unwrap();
+        let core = motion.animation_core.unwrap();
+
+        let time = core.get_time(5, 10, 24.0);
+        assert_eq!(time, 5000.0);
+
+        let transform = core.get_transform(time);
+        assert_eq!(transform.x, 50.0);
+        assert_eq!(transform.y, 0.0);
+        assert_eq!(transform.scale_x, 1.0);
+        assert!((transform.rotation - 78.75).abs() < 1e-6);
+
+        let color_transform = core.get_color_transform(time).unwrap();
+        assert_eq!(color_transform.multipliers[3], 0.5);
+    }
+
+    #[test]
+    fn time_without_duration() {
+        let motion: MotionObjectXml = quick_xml::de::from_str(
+            r#"<motionObjectXML><AnimationCore TimeScale="24000" Version="1"/></motionObjectXML>"#,
+        )
+        .unwrap();
+        let core = motion.animation_core.unwrap();
+
+        assert_eq!(core.get_time(3, 10, 24.0), 3000.0);
+        assert_eq!(core.get_time(3, 10, 12.0), 6000.0);
+    }
+
+    #[test]
+    fn disabled_advanced_color() {
+        let motion: MotionObjectXml = quick_xml::de::from_str(
+            r#"<motionObjectXML>
+                <AnimationCore TimeScale="24000" Version="1" duration="10000">
+                    <PropertyContainer id="headContainer">
+                        <PropertyContainer id="Colors">
+                            <PropertyContainer id="Advanced_Color">
+                                <Property enabled="0" id="Red_Multiplier" ignoreTimeMap="0">
+                                    <Keyframe anchor="0,50" timevalue="0"/>
+                                </Property>
+                                <Property enabled="0" id="Red_Offset" ignoreTimeMap="0">
+                                    <Keyframe anchor="0,255" timevalue="0"/>
+                                </Property>
+                            </PropertyContainer>
+                        </PropertyContainer>
+                    </PropertyContainer>
+                </AnimationCore>
+            </motionObjectXML>"#,
+        )
+        .unwrap();
+        let core = motion.animation_core.unwrap();
+
+        assert!(core.get_color_transform(0.0).is_none());
+    }
+}
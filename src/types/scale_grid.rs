@@ -0,0 +1,158 @@
+use crate::types::matrix::TWIPS_PER_PIXEL;
+
+/// The 9-slice scaling grid of a symbol, in pixels.
+///
+/// When an instance of the symbol is scaled, the corners keep their size, the edges are scaled along their length,
+/// and the center is scaled in both directions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleGrid {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+}
+
+impl ScaleGrid {
+    /// Get the mapping of points of a symbol with this grid, scaled by `scale_x` and `scale_y`.
+    ///
+    /// `bounds` is the bounding box of the symbol, in twips.
+    pub fn get_mapping(
+        &self,
+        bounds: &euclid::Box2D<f64, euclid::UnknownUnit>,
+        scale_x: f64,
+        scale_y: f64,
+    ) -> ScaleGridMapping {
+        ScaleGridMapping {
+            x: AxisMapping::new(
+                bounds.min.x,
+                bounds.max.x,
+                self.left * TWIPS_PER_PIXEL,
+                self.right * TWIPS_PER_PIXEL,
+                scale_x,
+            ),
+            y: AxisMapping::new(
+                bounds.min.y,
+                bounds.max.y,
+                self.top * TWIPS_PER_PIXEL,
+                self.bottom * TWIPS_PER_PIXEL,
+                scale_y,
+            ),
+        }
+    }
+}
+
+/// Maps points of a symbol, in twips, to where they are drawn when it is scaled with a 9-slice grid.
+///
+/// The grid only applies to the shapes of the symbol. Its other elements are scaled by the instance matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleGridMapping {
+    x: AxisMapping,
+    y: AxisMapping,
+}
+
+impl ScaleGridMapping {
+    /// Map a point of the symbol to where it is drawn, replacing the scale of the instance matrix.
+    pub fn map_point(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.x.map(x), self.y.map(y))
+    }
+
+    /// Map a point of the symbol to the point that the scale of the instance matrix moves to where it is drawn.
+    ///
+    /// Shapes mapped this way are drawn with the full instance matrix, like the other elements of the symbol.
+    pub fn map_point_unscaled(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.x.map(x) / self.x.scale, self.y.map(y) / self.y.scale)
+    }
+}
+
+/// A piecewise linear mapping of one axis of a 9-slice grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxisMapping {
+    min: f64,
+    grid_min: f64,
+    grid_max: f64,
+
+    /// Where the scaled symbol starts.
+    origin: f64,
+
+    /// The scale of the start and end slices.
+    corner_scale: f64,
+
+    /// The scale of the center slice.
+    center_scale: f64,
+
+    /// The scale of the instance matrix.
+    scale: f64,
+}
+
+impl AxisMapping {
+    fn new(min: f64, max: f64, grid_min: f64, grid_max: f64, scale: f64) -> Self {
+        let grid_min = grid_min.max(min).min(max);
+        let grid_max = grid_max.max(grid_min).min(max);
+
+        let fixed = (grid_min - min) + (max - grid_max);
+        let center = grid_max - grid_min;
+        let scaled = (max - min) * scale;
+
+        // If the symbol is scaled smaller than its corners, the corners shrink and the center disappears.
+        let (corner_scale, center_scale) = if scaled >= fixed {
+            let center_scale = if center > 0.0 {
+                (scaled - fixed) / center
+            } else {
+                0.0
+            };
+            (1.0, center_scale)
+        } else {
+            (scaled / fixed, 0.0)
+        };
+
+        Self {
+            min,
+            grid_min,
+            grid_max,
+            origin: min * scale,
+            corner_scale,
+            center_scale,
+            scale,
+        }
+    }
+
+    fn map(&self, value: f64) -> f64 {
+        let start = (value.min(self.grid_min) - self.min) * self.corner_scale;
+        let center =
+            (value.max(self.grid_min).min(self.grid_max) - self.grid_min) * self.center_scale;
+        let end = (value.max(self.grid_max) - self.grid_max) * self.corner_scale;
+
+        self.origin + start + center + end
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn corners_keep_their_size() {
+        let grid = ScaleGrid {
+            left: 10.0,
+            right: 90.0,
+            top: 10.0,
+            bottom: 40.0,
+        };
+        let bounds = euclid::Box2D::new(euclid::point2(0.0, 0.0), euclid::point2(2000.0, 1000.0));
+        let mapping = grid.get_mapping(&bounds, 2.0, 1.0);
+
+        assert_eq!(mapping.map_point(0.0, 0.0), (0.0, 0.0));
+        assert_eq!(mapping.map_point(200.0, 200.0), (200.0, 200.0));
+        assert_eq!(mapping.map_point(1000.0, 500.0), (2000.0, 500.0));
+        assert_eq!(mapping.map_point(1800.0, 800.0), (3800.0, 800.0));
+        assert_eq!(mapping.map_point(2000.0, 1000.0), (4000.0, 1000.0));
+        assert_eq!(mapping.map_point_unscaled(200.0, 200.0), (100.0, 200.0));
+        assert_eq!(mapping.map_point_unscaled(2000.0, 1000.0), (2000.0, 1000.0));
+
+        // Too small to fit the corners
+        let mapping = grid.get_mapping(&bounds, 0.05, 1.0);
+        assert_eq!(mapping.map_point(200.0, 0.0).0, 50.0);
+        assert_eq!(mapping.map_point(1800.0, 0.0).0, 50.0);
+        assert_eq!(mapping.map_point(2000.0, 0.0).0, 100.0);
+    }
+}
@@ -0,0 +1,43 @@
+//! Deserializing the xml documents of a fla.
+
+use std::borrow::Cow;
+
+/// Deserialize xml, keeping the whitespace of the characters of text runs.
+///
+/// The xml deserializer trims whitespace from text, which would drop spaces at the edges of a run.
+/// It does not trim CDATA, so the characters of text runs are wrapped in CDATA.
+/// Documents without text runs are deserialized as they are.
+pub(crate) fn from_str<T: serde::de::DeserializeOwned>(xml: &str) -> Result<T, quick_xml::DeError> {
+    quick_xml::de::from_str(&wrap_characters(xml))
+}
+
+/// Wrap the content of every `<characters>` element in CDATA.
+///
+/// The content is kept escaped, as the deserializer unescapes CDATA like text.
+/// Escaped text can not hold the `]]>` closing CDATA.
+fn wrap_characters(xml: &str) -> Cow<'_, str> {
+    if !xml.contains("<characters>") {
+        return Cow::Borrowed(xml);
+    }
+
+    Cow::Owned(
+        xml.replace("<characters>", "<characters><![CDATA[")
+            .replace("</characters>", "]]></characters>"),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_wraps_characters() {
+        let xml = "<DOMSymbolItem><timeline/></DOMSymbolItem>";
+        assert!(matches!(wrap_characters(xml), Cow::Borrowed(_)));
+
+        assert_eq!(
+            wrap_characters("<run><characters> a&amp;b </characters></run>"),
+            "<run><characters><![CDATA[ a&amp;b ]]></characters></run>"
+        );
+    }
+}